@@ -0,0 +1,548 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::keyboard::{KeyboardQuery, ModifierState};
+use crate::mouse::MouseQuery;
+use crate::names::KeyName;
+use crate::shortcut::{ModifierMatch, ParseShortcutError, Platform, Shortcut};
+
+/// A single input that can trigger an action: a key (with modifiers) or a
+/// mouse button.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Binding<Key, Button> {
+    Key(Shortcut<Key>),
+    Button(Button),
+}
+
+impl<Key, Button> Binding<Key, Button>
+where
+    Button: PartialEq,
+{
+    /// Returns `true` if the binding was pressed this frame.
+    pub fn pressed<K, M>(&self, keyboard: &K, mouse: &M, rule: ModifierMatch) -> bool
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+        M: MouseQuery<Button = Button>,
+    {
+        match self {
+            Binding::Key(shortcut) => shortcut.pressed(keyboard, rule),
+            Binding::Button(button) => mouse.pressed(button),
+        }
+    }
+
+    /// Returns `true` if the binding is currently held down.
+    pub fn down<K, M>(&self, keyboard: &K, mouse: &M, rule: ModifierMatch) -> bool
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+        M: MouseQuery<Button = Button>,
+    {
+        match self {
+            Binding::Key(shortcut) => shortcut.down(keyboard, rule),
+            Binding::Button(button) => mouse.down(button),
+        }
+    }
+}
+
+impl<Key: KeyName, Button: KeyName> FromStr for Binding<Key, Button> {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Button::from_name(s.trim()) {
+            Some(button) => Ok(Binding::Button(button)),
+            None => s.parse().map(Binding::Key),
+        }
+    }
+}
+
+impl<Key: KeyName, Button: KeyName> fmt::Display for Binding<Key, Button> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            // Always use the `Ctrl+Shift+Key` form so files are portable.
+            Binding::Key(shortcut) => shortcut.display_for(Platform::Linux).fmt(f),
+            Binding::Button(button) => f.write_str(&button.name()),
+        }
+    }
+}
+
+/// A set of named actions, each bound to zero or more inputs.
+///
+/// Bindings can be saved and loaded in a simple, human-editable format, with
+/// one action per line:
+///
+/// ```text
+/// # Comments start with `#`.
+/// jump = Space, MouseRight
+/// save = Ctrl+S
+/// unbound_action =
+/// ```
+///
+/// Bindings containing `#`, `,` or `"` are written in double quotes, with
+/// `\\` and `\"` escaping a backslash and a quote inside them:
+///
+/// ```text
+/// hash = "#"
+/// next_field = Tab, "Ctrl+,"
+/// ```
+///
+/// Action names may only contain letters, digits, `_`, `-` and `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bindings<Key, Button> {
+    actions: Vec<(String, Vec<Binding<Key, Button>>)>,
+}
+
+impl<Key, Button> Default for Bindings<Key, Button>
+where
+    Key: PartialEq,
+    Button: PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Key, Button> Bindings<Key, Button>
+where
+    Key: PartialEq,
+    Button: PartialEq,
+{
+    pub fn new() -> Self {
+        Bindings {
+            actions: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if `action` can be used as an action name, so that it
+    /// can be written to and read back from a bindings file.
+    pub fn is_valid_action(action: &str) -> bool {
+        !action.is_empty()
+            && action
+                .chars()
+                .all(|c| c.is_alphanumeric() || "_-.".contains(c))
+    }
+
+    /// Add a binding to an action, creating the action if necessary.
+    ///
+    /// Panics if the action name isn't valid (see `is_valid_action`).
+    pub fn bind<A: Into<String>>(&mut self, action: A, binding: Binding<Key, Button>) -> &mut Self {
+        let bindings = self.entry(action.into());
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Builder-style version of `bind`.
+    pub fn with<A: Into<String>>(mut self, action: A, binding: Binding<Key, Button>) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Replace all bindings for an action.
+    ///
+    /// Panics if the action name isn't valid (see `is_valid_action`).
+    pub fn set<A: Into<String>>(
+        &mut self,
+        action: A,
+        bindings: Vec<Binding<Key, Button>>,
+    ) -> &mut Self {
+        *self.entry(action.into()) = bindings;
+        self
+    }
+
+    /// Remove a single binding from an action. The action itself remains,
+    /// even if it has no bindings left.
+    pub fn unbind(&mut self, action: &str, binding: &Binding<Key, Button>) -> &mut Self {
+        if let Some((_, bindings)) = self.actions.iter_mut().find(|(a, _)| a == action) {
+            bindings.retain(|b| b != binding);
+        }
+        self
+    }
+
+    /// Remove an action and all of its bindings.
+    pub fn remove(&mut self, action: &str) -> &mut Self {
+        self.actions.retain(|(a, _)| a != action);
+        self
+    }
+
+    /// Returns `true` if the action exists (even if it has no bindings).
+    pub fn contains(&self, action: &str) -> bool {
+        self.actions.iter().any(|(a, _)| a == action)
+    }
+
+    /// Returns the bindings for an action, or an empty slice if it doesn't
+    /// exist.
+    pub fn bindings(&self, action: &str) -> &[Binding<Key, Button>] {
+        self.actions
+            .iter()
+            .find(|(a, _)| a == action)
+            .map(|(_, b)| b.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over all actions and their bindings.
+    pub fn actions(&self) -> impl Iterator<Item = (&str, &[Binding<Key, Button>])> {
+        self.actions.iter().map(|(a, b)| (a.as_str(), b.as_slice()))
+    }
+
+    /// Add any actions from `defaults` that are missing, leaving existing
+    /// actions untouched.
+    ///
+    /// This is intended for loading a player's saved bindings after new
+    /// actions have been added to the game: their customizations are kept,
+    /// and the new actions get their default bindings. Actions that are not
+    /// in `defaults` are kept so that they survive a round trip.
+    pub fn merge_defaults(&mut self, defaults: &Self) -> &mut Self
+    where
+        Key: Clone,
+        Button: Clone,
+    {
+        for (action, bindings) in &defaults.actions {
+            if !self.contains(action) {
+                self.actions.push((action.clone(), bindings.clone()));
+            }
+        }
+        self
+    }
+
+    /// Returns `true` if any binding for the action was pressed this frame.
+    pub fn pressed<K, M>(&self, action: &str, keyboard: &K, mouse: &M, rule: ModifierMatch) -> bool
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+        M: MouseQuery<Button = Button>,
+    {
+        self.bindings(action)
+            .iter()
+            .any(|b| b.pressed(keyboard, mouse, rule))
+    }
+
+    /// Returns `true` if any binding for the action is held down.
+    pub fn down<K, M>(&self, action: &str, keyboard: &K, mouse: &M, rule: ModifierMatch) -> bool
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+        M: MouseQuery<Button = Button>,
+    {
+        self.bindings(action)
+            .iter()
+            .any(|b| b.down(keyboard, mouse, rule))
+    }
+
+    fn entry(&mut self, action: String) -> &mut Vec<Binding<Key, Button>> {
+        assert!(
+            Self::is_valid_action(&action),
+            "invalid action name `{}`",
+            action
+        );
+        let index = match self.actions.iter().position(|(a, _)| *a == action) {
+            Some(index) => index,
+            None => {
+                self.actions.push((action, Vec::new()));
+                self.actions.len() - 1
+            }
+        };
+        &mut self.actions[index].1
+    }
+}
+
+impl<Key, Button> FromStr for Bindings<Key, Button>
+where
+    Key: KeyName + PartialEq,
+    Button: KeyName + PartialEq,
+{
+    type Err = ParseBindingsError;
+
+    /// Parse bindings, reporting every invalid line rather than just the
+    /// first.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bindings = Bindings::new();
+        let mut errors = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let comment = unquoted(line, '#').next().unwrap_or(line.len());
+            let line = line[..comment].trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some((action, values)) = line.split_once('=') else {
+                errors.push(BindingError {
+                    line: line_number,
+                    kind: BindingErrorKind::MissingEquals,
+                });
+                continue;
+            };
+
+            let action = action.trim();
+            if !Self::is_valid_action(action) {
+                errors.push(BindingError {
+                    line: line_number,
+                    kind: BindingErrorKind::InvalidAction(action.to_owned()),
+                });
+                continue;
+            }
+            if bindings.contains(action) {
+                errors.push(BindingError {
+                    line: line_number,
+                    kind: BindingErrorKind::DuplicateAction(action.to_owned()),
+                });
+                continue;
+            }
+
+            let mut parsed = Vec::new();
+            let mut start = 0;
+            let ends = unquoted(values, ',').chain(Some(values.len()));
+            for end in ends {
+                let value = values[start..end].trim();
+                start = end + 1;
+                if value.is_empty() {
+                    continue;
+                }
+                let Some(value) = unquote(value) else {
+                    errors.push(BindingError {
+                        line: line_number,
+                        kind: BindingErrorKind::InvalidQuotes(value.to_owned()),
+                    });
+                    continue;
+                };
+                match value.parse() {
+                    Ok(binding) => parsed.push(binding),
+                    Err(error) => errors.push(BindingError {
+                        line: line_number,
+                        kind: BindingErrorKind::InvalidBinding(error),
+                    }),
+                }
+            }
+            bindings.set(action, parsed);
+        }
+
+        match errors.is_empty() {
+            true => Ok(bindings),
+            false => Err(ParseBindingsError { errors }),
+        }
+    }
+}
+
+impl<Key: KeyName, Button: KeyName> fmt::Display for Bindings<Key, Button> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (action, bindings) in &self.actions {
+            write!(f, "{} =", action)?;
+            for (i, binding) in bindings.iter().enumerate() {
+                let separator = if i == 0 { " " } else { ", " };
+                write!(f, "{}{}", separator, quote(&binding.to_string()))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the byte offsets of each `target` character in `s` that is not
+/// inside a quoted binding.
+fn unquoted(s: &str, target: char) -> impl Iterator<Item = usize> + '_ {
+    let mut quoted = false;
+    let mut escaped = false;
+    s.char_indices().filter_map(move |(i, c)| {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == target && !quoted => return Some(i),
+            _ => (),
+        }
+        None
+    })
+}
+
+/// Returns the contents of a binding that may be quoted, or `None` if its
+/// quotes don't match.
+fn unquote(value: &str) -> Option<Cow<'_, str>> {
+    let Some(inner) = value.strip_prefix('"') else {
+        return Some(Cow::Borrowed(value));
+    };
+    let mut chars = inner.strip_suffix('"')?.chars();
+    let mut unquoted = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.push(chars.next()?),
+            '"' => return None,
+            c => unquoted.push(c),
+        }
+    }
+    Some(Cow::Owned(unquoted))
+}
+
+/// Quotes a binding if it contains characters with a meaning in the file.
+fn quote(binding: &str) -> Cow<'_, str> {
+    match binding.contains(['#', ',', '"']) {
+        true => {
+            let escaped = binding.replace('\\', "\\\\").replace('"', "\\\"");
+            Cow::Owned(format!("\"{}\"", escaped))
+        }
+        false => Cow::Borrowed(binding),
+    }
+}
+
+/// A problem found on a particular line while parsing `Bindings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingError {
+    /// The line the error occurred on, starting from `1`.
+    pub line: usize,
+    pub kind: BindingErrorKind,
+}
+
+/// The kinds of problems that can occur while parsing `Bindings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingErrorKind {
+    /// The line was not of the form `action = bindings`.
+    MissingEquals,
+
+    /// The action name was empty or contained invalid characters.
+    InvalidAction(String),
+
+    /// The action was already defined on an earlier line.
+    DuplicateAction(String),
+
+    /// A quoted binding was not closed, or had quotes in the wrong place.
+    InvalidQuotes(String),
+
+    /// A binding could not be parsed, for example due to an unknown key name.
+    InvalidBinding(ParseShortcutError),
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            BindingErrorKind::MissingEquals => write!(f, "expected `action = bindings`"),
+            BindingErrorKind::InvalidAction(action) => {
+                write!(f, "invalid action name `{}`", action)
+            }
+            BindingErrorKind::DuplicateAction(action) => write!(f, "duplicate action `{}`", action),
+            BindingErrorKind::InvalidQuotes(binding) => {
+                write!(f, "mismatched quotes in `{}`", binding)
+            }
+            BindingErrorKind::InvalidBinding(error) => error.fmt(f),
+        }
+    }
+}
+
+/// An error returned when parsing `Bindings` fails, listing every problem
+/// that was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBindingsError {
+    pub errors: Vec<BindingError>,
+}
+
+impl fmt::Display for ParseBindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            error.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseBindingsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keyboard, Modifiers, Mouse, MouseInterface};
+    use std::borrow::Cow;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Button(u8);
+
+    impl KeyName for Button {
+        fn name(&self) -> Cow<'static, str> {
+            Cow::Owned(format!("Mouse{}", self.0))
+        }
+
+        fn from_name(name: &str) -> Option<Self> {
+            name.strip_prefix("Mouse")?.parse().ok().map(Button)
+        }
+    }
+
+    type TestBindings = Bindings<char, Button>;
+
+    const FILE: &str = "# Controls
+jump = j, Mouse1
+save = Ctrl+s
+menu =
+";
+
+    #[test]
+    fn bindings_round_trip_through_text() {
+        let bindings: TestBindings = FILE.parse().unwrap();
+        assert_eq!(
+            bindings.bindings("jump"),
+            &[Binding::Key(Shortcut::key('j')), Binding::Button(Button(1))]
+        );
+        assert!(bindings.contains("menu"));
+        assert_eq!(bindings.to_string().parse::<TestBindings>(), Ok(bindings));
+    }
+
+    #[test]
+    fn bindings_report_errors_with_lines() {
+        let error = "jump = j\nsave = Hyper+s, Mouse1\njump = k\noops\n"
+            .parse::<TestBindings>()
+            .unwrap_err();
+        let lines: Vec<usize> = error.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![2, 3, 4]);
+        assert_eq!(
+            error.errors[0].to_string(),
+            "line 2: unknown modifier `Hyper`"
+        );
+    }
+
+    #[test]
+    fn special_characters_are_quoted() {
+        let mut bindings = TestBindings::new();
+        for key in ['#', ',', '"', '\\'] {
+            bindings.bind("type", Binding::Key(Shortcut::key(key)));
+        }
+        bindings.bind("type", "Ctrl+,".parse().unwrap());
+        let text = bindings.to_string();
+        assert_eq!(text, "type = \"#\", \",\", \"\\\"\", \\, \"Ctrl+,\"\n");
+        assert_eq!(text.parse::<TestBindings>(), Ok(bindings));
+
+        let error = "type = \"#\n".parse::<TestBindings>().unwrap_err();
+        assert_eq!(
+            error.errors[0].kind,
+            BindingErrorKind::InvalidQuotes("\"#".to_owned())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid action name")]
+    fn binding_an_invalid_action_panics() {
+        TestBindings::new().bind("save game", Binding::Button(Button(1)));
+    }
+
+    #[test]
+    fn merge_defaults_keeps_customized_actions() {
+        let mut saved: TestBindings = "jump = k\n".parse().unwrap();
+        let defaults: TestBindings = "jump = j\nduck = d\n".parse().unwrap();
+        saved.merge_defaults(&defaults);
+        assert_eq!(saved.bindings("jump"), &[Binding::Key(Shortcut::key('k'))]);
+        assert_eq!(saved.bindings("duck"), &[Binding::Key(Shortcut::key('d'))]);
+    }
+
+    #[test]
+    fn action_pressed_by_any_binding() {
+        let bindings: TestBindings = FILE.parse().unwrap();
+        let keyboard: Keyboard<char, Modifiers> = Keyboard::new();
+        let mut mouse: Mouse<Button, f64> = Mouse::new();
+        mouse.press(Button(1));
+        assert!(bindings.pressed("jump", &keyboard, &mouse, ModifierMatch::Exact));
+        assert!(!bindings.pressed("save", &keyboard, &mouse, ModifierMatch::Exact));
+    }
+}
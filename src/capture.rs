@@ -0,0 +1,280 @@
+use crate::bindings::Binding;
+use crate::keyboard::{KeyboardQuery, ModifierState, Modifiers};
+use crate::mouse::MouseQuery;
+use crate::shortcut::Shortcut;
+
+/// The outcome of a frame of `Capture`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureResult<Key, Button> {
+    /// No new input has been captured yet.
+    Pending,
+
+    /// An input was captured.
+    Captured(Binding<Key, Button>),
+
+    /// A cancel key was pressed.
+    Cancelled,
+}
+
+/// Captures the next input pressed by the player, for "press a key to bind"
+/// menus.
+///
+/// Inputs that are already held when capture begins are ignored until they
+/// are released. Pressing a non-modifier key while modifiers are held
+/// captures the whole chord (such as Ctrl+S), while pressing and releasing a
+/// modifier key on its own captures just that key, along with the modifier
+/// flag it sets (so a lone Ctrl is bound as Ctrl+Ctrl, which is how it looks
+/// to `Shortcut::pressed` while held).
+#[derive(Debug, Clone)]
+pub struct Capture<Key, Button> {
+    cancel_keys: Vec<Key>,
+    reserved_keys: Vec<Key>,
+    reserved_buttons: Vec<Button>,
+    modifier_keys: Vec<Key>,
+    held_keys: Vec<Key>,
+    held_buttons: Vec<Button>,
+    pending_modifier: Option<(Key, Modifiers)>,
+}
+
+impl<Key, Button> Default for Capture<Key, Button>
+where
+    Key: Clone + PartialEq,
+    Button: Clone + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Key, Button> Capture<Key, Button>
+where
+    Key: Clone + PartialEq,
+    Button: Clone + PartialEq,
+{
+    pub fn new() -> Self {
+        Capture {
+            cancel_keys: Vec::new(),
+            reserved_keys: Vec::new(),
+            reserved_buttons: Vec::new(),
+            modifier_keys: Vec::new(),
+            held_keys: Vec::new(),
+            held_buttons: Vec::new(),
+            pending_modifier: None,
+        }
+    }
+
+    /// Add a key that cancels capture instead of being bound (such as Escape).
+    pub fn cancel_key(mut self, key: Key) -> Self {
+        self.cancel_keys.push(key);
+        self
+    }
+
+    /// Add a key that can never be bound and is ignored during capture.
+    pub fn reserve_key(mut self, key: Key) -> Self {
+        self.reserved_keys.push(key);
+        self
+    }
+
+    /// Add a mouse button that can never be bound and is ignored during
+    /// capture.
+    pub fn reserve_button(mut self, button: Button) -> Self {
+        self.reserved_buttons.push(button);
+        self
+    }
+
+    /// Add keys that act as modifiers, and so only complete a capture when
+    /// released on their own.
+    pub fn modifier_keys<I: IntoIterator<Item = Key>>(mut self, keys: I) -> Self {
+        self.modifier_keys.extend(keys);
+        self
+    }
+
+    /// Begin capturing. Any keys or buttons currently held are ignored until
+    /// they are released.
+    pub fn begin<K, M>(&mut self, keyboard: &K, mouse: &M) -> &mut Self
+    where
+        K: KeyboardQuery<Key = Key>,
+        M: MouseQuery<Button = Button>,
+    {
+        self.held_keys = keyboard.keys_down().cloned().collect();
+        self.held_buttons = mouse.buttons_down().cloned().collect();
+        self.pending_modifier = None;
+        self
+    }
+
+    /// Check this frame's input for a newly pressed key, chord or button.
+    /// This should be called once per frame until it returns something other
+    /// than `CaptureResult::Pending`.
+    pub fn update<K, M>(&mut self, keyboard: &K, mouse: &M) -> CaptureResult<Key, Button>
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+        M: MouseQuery<Button = Button>,
+    {
+        self.held_keys.retain(|k| keyboard.down(k));
+        self.held_buttons.retain(|b| mouse.down(b));
+
+        // Prefer a normal key, so a chord pressed within a single frame isn't
+        // captured as a lone modifier.
+        let unbound = |k: &&Key| !self.held_keys.contains(k) && !self.reserved_keys.contains(k);
+        let pressed_key = keyboard
+            .keys_pressed()
+            .filter(unbound)
+            .find(|k| !self.modifier_keys.contains(k))
+            .or_else(|| keyboard.keys_pressed().find(unbound));
+
+        let modifiers = keyboard
+            .modifiers()
+            .map(ModifierState::to_modifiers)
+            .unwrap_or_default();
+
+        if let Some(key) = pressed_key {
+            if self.cancel_keys.contains(key) {
+                return CaptureResult::Cancelled;
+            }
+            if self.modifier_keys.contains(key) {
+                self.pending_modifier = Some((key.clone(), Modifiers::default()));
+            } else {
+                return CaptureResult::Captured(Binding::Key(Shortcut::new(
+                    modifiers,
+                    key.clone(),
+                )));
+            }
+        }
+
+        if let Some((modifier, mut held)) = self.pending_modifier.take() {
+            // Modifier state can lag behind key presses, and is usually
+            // cleared by the time the key is released, so remember any
+            // flags seen while the modifier was down.
+            held.ctrl |= modifiers.ctrl;
+            held.alt |= modifiers.alt;
+            held.shift |= modifiers.shift;
+            held.logo |= modifiers.logo;
+            if keyboard.released(&modifier) {
+                return CaptureResult::Captured(Binding::Key(Shortcut::new(held, modifier)));
+            }
+            self.pending_modifier = Some((modifier, held));
+        }
+
+        let pressed_button = mouse
+            .buttons_pressed()
+            .find(|b| !self.held_buttons.contains(b) && !self.reserved_buttons.contains(b));
+        match pressed_button {
+            Some(button) => CaptureResult::Captured(Binding::Button(button.clone())),
+            None => CaptureResult::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keyboard, KeyboardInterface, ModifierMatch, Mouse, MouseInterface};
+
+    const ESCAPE: char = '\u{1b}';
+    const CTRL: char = '^';
+
+    type TestKeyboard = Keyboard<char, Modifiers>;
+    type TestMouse = Mouse<u8, f64>;
+
+    fn capture() -> Capture<char, u8> {
+        Capture::new()
+            .cancel_key(ESCAPE)
+            .reserve_button(0)
+            .modifier_keys([CTRL])
+    }
+
+    #[test]
+    fn capture_ignores_held_and_reserved_inputs() {
+        let mut keyboard = TestKeyboard::new();
+        let mut mouse = TestMouse::new();
+        keyboard.press('a');
+
+        let mut capture = capture();
+        capture.begin(&keyboard, &mouse);
+        mouse.press(0);
+        assert_eq!(capture.update(&keyboard, &mouse), CaptureResult::Pending);
+
+        mouse.press(1);
+        assert_eq!(
+            capture.update(&keyboard, &mouse),
+            CaptureResult::Captured(Binding::Button(1))
+        );
+    }
+
+    #[test]
+    fn capture_chord_with_modifiers() {
+        let mut keyboard = TestKeyboard::new();
+        let mouse = TestMouse::new();
+        let mut capture = capture();
+        capture.begin(&keyboard, &mouse);
+
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        keyboard.press(CTRL).set_modifiers(ctrl);
+        assert_eq!(capture.update(&keyboard, &mouse), CaptureResult::Pending);
+
+        keyboard.clear_presses();
+        keyboard.press('s');
+        assert_eq!(
+            capture.update(&keyboard, &mouse),
+            CaptureResult::Captured(Binding::Key(Shortcut::new(ctrl, 's')))
+        );
+    }
+
+    #[test]
+    fn capture_chord_pressed_in_one_frame() {
+        let mut keyboard = TestKeyboard::new();
+        let mouse = TestMouse::new();
+        let mut capture = capture();
+        capture.begin(&keyboard, &mouse);
+
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        keyboard.press(CTRL).press('s').set_modifiers(ctrl);
+        assert_eq!(
+            capture.update(&keyboard, &mouse),
+            CaptureResult::Captured(Binding::Key(Shortcut::new(ctrl, 's')))
+        );
+    }
+
+    #[test]
+    fn capture_lone_modifier_on_release() {
+        let mut keyboard = TestKeyboard::new();
+        let mouse = TestMouse::new();
+        let mut capture = capture();
+        capture.begin(&keyboard, &mouse);
+
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        keyboard.press(CTRL).set_modifiers(ctrl);
+        assert_eq!(capture.update(&keyboard, &mouse), CaptureResult::Pending);
+        keyboard.clear_presses();
+        keyboard.release(CTRL).set_modifiers(Modifiers::default());
+        let binding = match capture.update(&keyboard, &mouse) {
+            CaptureResult::Captured(binding) => binding,
+            other => panic!("expected a capture, got {:?}", other),
+        };
+        assert_eq!(binding, Binding::Key(Shortcut::new(ctrl, CTRL)));
+
+        keyboard.clear_presses();
+        keyboard.press(CTRL).set_modifiers(ctrl);
+        assert!(binding.pressed(&keyboard, &mouse, ModifierMatch::Exact));
+    }
+
+    #[test]
+    fn capture_cancelled_by_cancel_key() {
+        let mut keyboard = TestKeyboard::new();
+        let mouse = TestMouse::new();
+        let mut capture = capture();
+        capture.begin(&keyboard, &mouse);
+        keyboard.press(ESCAPE);
+        assert_eq!(capture.update(&keyboard, &mouse), CaptureResult::Cancelled);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::bindings::{Binding, Bindings};
+use crate::keyboard::Modifiers;
+use crate::shortcut::{ModifierMatch, Shortcut};
+
+/// The way two bindings overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Both actions are bound to exactly the same key chord or mouse button.
+    Duplicate,
+
+    /// Both actions use the same key, and one chord's modifiers are a subset
+    /// of the other's (such as S and Ctrl+S). These only trigger together
+    /// when matching with `ModifierMatch::Subset`.
+    Chord,
+
+    /// One binding is a modifier key on its own, which is pressed as part of
+    /// the other's chord (such as Left Ctrl and Ctrl+S). Modifier keys must
+    /// be registered with `ConflictAnalyzer::modifier_key` to be detected.
+    Modifier,
+}
+
+/// One side of a `Conflict`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BindingRef<'a, Key, Button> {
+    pub context: &'a str,
+    pub action: &'a str,
+    pub binding: &'a Binding<Key, Button>,
+}
+
+impl<Key, Button> Clone for BindingRef<'_, Key, Button> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Key, Button> Copy for BindingRef<'_, Key, Button> {}
+
+/// Two bindings, for different actions, that can be triggered by the same
+/// input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict<'a, Key, Button> {
+    pub kind: ConflictKind,
+    pub first: BindingRef<'a, Key, Button>,
+    pub second: BindingRef<'a, Key, Button>,
+}
+
+/// Finds bindings that overlap within, or across, contexts.
+///
+/// Each context is a set of `Bindings` that are active at the same time,
+/// such as "gameplay" or "menu". Bindings are only compared within a
+/// context, unless two contexts are marked as overlapping (for example a
+/// "global" context that is always active alongside the others).
+///
+/// Key chords and mouse buttons are both compared, so two actions sharing a
+/// mouse button are reported just like two actions sharing a key. A key and
+/// a mouse button are always different inputs, so they never conflict with
+/// each other.
+#[derive(Debug, Clone)]
+pub struct ConflictAnalyzer<'a, Key, Button> {
+    contexts: Vec<(&'a str, &'a Bindings<Key, Button>)>,
+    overlaps: Vec<(&'a str, &'a str)>,
+    modifier_keys: Vec<(Key, Modifiers)>,
+}
+
+impl<Key, Button> Default for ConflictAnalyzer<'_, Key, Button>
+where
+    Key: PartialEq,
+    Button: PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, Key, Button> ConflictAnalyzer<'a, Key, Button>
+where
+    Key: PartialEq,
+    Button: PartialEq,
+{
+    pub fn new() -> Self {
+        ConflictAnalyzer {
+            contexts: Vec::new(),
+            overlaps: Vec::new(),
+            modifier_keys: Vec::new(),
+        }
+    }
+
+    /// Add a named context to analyze.
+    pub fn context(mut self, name: &'a str, bindings: &'a Bindings<Key, Button>) -> Self {
+        self.contexts.push((name, bindings));
+        self
+    }
+
+    /// Mark two contexts as being active at the same time, so that bindings
+    /// in one can conflict with bindings in the other.
+    pub fn overlapping(mut self, a: &'a str, b: &'a str) -> Self {
+        self.overlaps.push((a, b));
+        self
+    }
+
+    /// Register a key that sets modifier flags while held (such as Left Ctrl
+    /// setting `ctrl`), so that binding it on its own is reported as
+    /// conflicting with chords that use those flags.
+    pub fn modifier_key(mut self, key: Key, flags: Modifiers) -> Self {
+        self.modifier_keys.push((key, flags));
+        self
+    }
+
+    /// Returns every conflict between bindings. Each pair of conflicting
+    /// bindings is reported once.
+    pub fn conflicts(&self) -> Vec<Conflict<'a, Key, Button>> {
+        let bindings: Vec<BindingRef<'a, Key, Button>> = self
+            .contexts
+            .iter()
+            .flat_map(|&(context, bindings)| {
+                bindings.actions().flat_map(move |(action, bindings)| {
+                    bindings.iter().map(move |binding| BindingRef {
+                        context,
+                        action,
+                        binding,
+                    })
+                })
+            })
+            .collect();
+
+        let mut conflicts = Vec::new();
+        for (i, first) in bindings.iter().enumerate() {
+            for second in &bindings[i + 1..] {
+                let same_action = first.context == second.context && first.action == second.action;
+                if same_action || !self.active_together(first.context, second.context) {
+                    continue;
+                }
+                let kind = ConflictKind::between(first.binding, second.binding).or_else(|| {
+                    self.modifier_conflict(first.binding, second.binding)
+                        .then_some(ConflictKind::Modifier)
+                });
+                if let Some(kind) = kind {
+                    conflicts.push(Conflict {
+                        kind,
+                        first: *first,
+                        second: *second,
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    fn modifier_conflict(&self, a: &Binding<Key, Button>, b: &Binding<Key, Button>) -> bool {
+        let (Binding::Key(a), Binding::Key(b)) = (a, b) else {
+            return false;
+        };
+        self.pressed_during(a, b) || self.pressed_during(b, a)
+    }
+
+    /// Returns `true` if `lone` is a modifier key that is pressed with the
+    /// right modifiers while entering `chord`.
+    fn pressed_during(&self, lone: &Shortcut<Key>, chord: &Shortcut<Key>) -> bool {
+        lone.key != chord.key
+            && ModifierMatch::Subset.matches(&lone.modifiers, &chord.modifiers)
+            && self.modifier_keys.iter().any(|(key, flags)| {
+                *key == lone.key && ModifierMatch::Subset.matches(flags, &chord.modifiers)
+            })
+    }
+
+    fn active_together(&self, a: &str, b: &str) -> bool {
+        a == b
+            || self
+                .overlaps
+                .iter()
+                .any(|&(x, y)| (x == a && y == b) || (x == b && y == a))
+    }
+}
+
+impl ConflictKind {
+    /// Returns how two bindings overlap, if they do.
+    pub fn between<Key, Button>(
+        a: &Binding<Key, Button>,
+        b: &Binding<Key, Button>,
+    ) -> Option<ConflictKind>
+    where
+        Key: PartialEq,
+        Button: PartialEq,
+    {
+        match (a, b) {
+            (Binding::Button(a), Binding::Button(b)) if a == b => Some(ConflictKind::Duplicate),
+            (Binding::Key(a), Binding::Key(b)) if a.key == b.key => {
+                if a.modifiers == b.modifiers {
+                    Some(ConflictKind::Duplicate)
+                } else if ModifierMatch::Subset.matches(&a.modifiers, &b.modifiers)
+                    || ModifierMatch::Subset.matches(&b.modifiers, &a.modifiers)
+                {
+                    Some(ConflictKind::Chord)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Modifiers, Shortcut};
+
+    type TestBindings = Bindings<char, u8>;
+
+    fn key(key: char) -> Binding<char, u8> {
+        Binding::Key(Shortcut::key(key))
+    }
+
+    fn ctrl(key: char) -> Binding<char, u8> {
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        Binding::Key(Shortcut::new(ctrl, key))
+    }
+
+    #[test]
+    fn detect_duplicates_and_chords() {
+        let gameplay = TestBindings::new()
+            .with("jump", key('s'))
+            .with("save", ctrl('s'))
+            .with("fire", Binding::Button(0))
+            .with("use", Binding::Button(0));
+        let conflicts = ConflictAnalyzer::new()
+            .context("gameplay", &gameplay)
+            .conflicts();
+
+        let found: Vec<_> = conflicts
+            .iter()
+            .map(|c| (c.kind, c.first.action, c.second.action))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (ConflictKind::Chord, "jump", "save"),
+                (ConflictKind::Duplicate, "fire", "use"),
+            ]
+        );
+    }
+
+    #[test]
+    fn separate_contexts_do_not_conflict_unless_overlapping() {
+        let gameplay = TestBindings::new().with("jump", key(' '));
+        let menu = TestBindings::new().with("select", key(' '));
+        let global = TestBindings::new().with("screenshot", key(' '));
+
+        let conflicts = ConflictAnalyzer::new()
+            .context("gameplay", &gameplay)
+            .context("menu", &menu)
+            .context("global", &global)
+            .overlapping("global", "gameplay")
+            .conflicts();
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].first.context, "gameplay");
+        assert_eq!(conflicts[0].second.context, "global");
+    }
+
+    #[test]
+    fn lone_modifier_keys_conflict_with_their_chords() {
+        const LEFT_CTRL: char = '^';
+        let ctrl_flag = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        let gameplay = TestBindings::new()
+            .with("crouch", key(LEFT_CTRL))
+            .with("save", ctrl('s'))
+            .with("jump", key(' '));
+        let conflicts = ConflictAnalyzer::new()
+            .context("gameplay", &gameplay)
+            .modifier_key(LEFT_CTRL, ctrl_flag)
+            .conflicts();
+
+        let found: Vec<_> = conflicts
+            .iter()
+            .map(|c| (c.kind, c.first.action, c.second.action))
+            .collect();
+        assert_eq!(found, vec![(ConflictKind::Modifier, "crouch", "save")]);
+    }
+
+    #[test]
+    fn unrelated_chords_do_not_conflict() {
+        let alt_s = Binding::Key(Shortcut::new(
+            Modifiers {
+                alt: true,
+                ..Default::default()
+            },
+            's',
+        ));
+        assert_eq!(ConflictKind::between(&ctrl('s'), &alt_s), None);
+        assert_eq!(
+            ConflictKind::between(&ctrl('s'), &key('s')),
+            Some(ConflictKind::Chord)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use smol_str::SmolStr;
+
+use crate::text::TextCommand;
+use crate::touch::TouchPhase;
+
+/// A single keyboard event, as recorded by a keyboard's event log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyboardEvent<Key> {
+    Pressed(Key),
+    Released(Key),
+
+    /// Text was entered. Control characters are not included.
+    Text(SmolStr),
+
+    /// A text editing command was triggered.
+    Command(TextCommand),
+}
+
+/// A single mouse event, as recorded by a mouse's event log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MouseEvent<Button, Coord> {
+    Pressed(Button),
+    Released(Button),
+
+    /// The pointer moved to the given position.
+    Moved([Coord; 2]),
+
+    /// The pointer entered the window.
+    Entered,
+
+    /// The pointer left the window.
+    Left,
+}
+
+/// A single touch event, as recorded by a touchpad's event log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TouchEvent<Id, Coord> {
+    pub id: Id,
+    pub position: [Coord; 2],
+    pub phase: TouchPhase,
+}
+
+/// The ordered list of events received by a device this frame. Recording is
+/// disabled by default, so devices that don't need it pay nothing.
+#[derive(Debug, Clone)]
+pub(crate) struct EventLog<E> {
+    enabled: bool,
+    events: Vec<E>,
+}
+
+impl<E> Default for EventLog<E> {
+    fn default() -> Self {
+        EventLog {
+            enabled: false,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<E> EventLog<E> {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.events = Vec::new();
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn record(&mut self, event: E) {
+        if self.enabled {
+            self.events.push(event);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.events.iter()
+    }
+}
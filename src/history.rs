@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The number of frames of presses and releases kept by each device by
+/// default.
+pub(crate) const DEFAULT_HISTORY_FRAMES: u32 = 60;
+
+/// The assumed length of a frame, until a device is told otherwise.
+pub(crate) const DEFAULT_FRAME_DURATION: Duration = Duration::from_nanos(16_666_667);
+
+/// How far back a buffered press query such as `pressed_within` looks.
+///
+/// A `u32` converts to a number of frames, and a `Duration` converts to an
+/// amount of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Within {
+    /// The given number of frames before the current one. `Frames(0)` only
+    /// considers the current frame.
+    Frames(u32),
+
+    /// Frames that started at most this long before the current one.
+    Duration(Duration),
+}
+
+impl From<u32> for Within {
+    fn from(frames: u32) -> Self {
+        Within::Frames(frames)
+    }
+}
+
+impl From<Duration> for Within {
+    fn from(duration: Duration) -> Self {
+        Within::Duration(duration)
+    }
+}
+
+/// A single press or release, tagged with the frame it happened on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Record<T> {
+    pub input: T,
+    pub frame: u64,
+    pub time: Duration,
+    pub pressed: bool,
+}
+
+/// Recent presses and releases, kept for a fixed number of frames.
+///
+/// Frames are counted by calls to `next_frame`, which devices call from
+/// `clear_presses`. Old records are dropped by age rather than count, so
+/// key repeat can't push out presses that are still within the window.
+#[derive(Debug, Clone)]
+pub(crate) struct History<T> {
+    frame: u64,
+    time: Duration,
+    frame_duration: Duration,
+    max_age: u32,
+    records: VecDeque<Record<T>>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        History {
+            frame: 0,
+            time: Duration::ZERO,
+            frame_duration: DEFAULT_FRAME_DURATION,
+            max_age: DEFAULT_HISTORY_FRAMES,
+            records: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> History<T> {
+    pub fn next_frame(&mut self) {
+        self.frame += 1;
+        self.time += self.frame_duration;
+        self.prune();
+    }
+
+    pub fn record(&mut self, input: T, pressed: bool) {
+        self.records.push_back(Record {
+            input,
+            frame: self.frame,
+            time: self.time,
+            pressed,
+        });
+    }
+
+    /// Set how many frames records are kept for, not counting the current
+    /// frame.
+    pub fn set_max_age(&mut self, frames: u32) {
+        self.max_age = frames;
+        self.prune();
+    }
+
+    /// Set the duration of the current frame, which is used to timestamp
+    /// the frames that follow it.
+    pub fn set_frame_duration(&mut self, duration: Duration) {
+        self.frame_duration = duration;
+    }
+
+    /// Returns the time at which the current frame started.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns the time at which the current frame ends.
+    pub fn now(&self) -> Duration {
+        self.time + self.frame_duration
+    }
+
+    /// Returns the most recent records first.
+    pub fn iter(&self) -> impl Iterator<Item = &Record<T>> {
+        self.records.iter().rev()
+    }
+
+    fn prune(&mut self) {
+        let oldest = self.frame.saturating_sub(self.max_age as u64);
+        while self.records.front().is_some_and(|r| r.frame < oldest) {
+            self.records.pop_front();
+        }
+    }
+}
+
+impl<T: PartialEq> History<T> {
+    /// Returns `true` if `input` was pressed (or released) within the given
+    /// number of frames or amount of time.
+    pub fn within(&self, input: &T, pressed: bool, within: Within) -> bool {
+        let recent = |r: &&Record<T>| match within {
+            Within::Frames(frames) => self.frame - r.frame <= frames as u64,
+            Within::Duration(duration) => self.time - r.time <= duration,
+        };
+        self.iter()
+            .take_while(recent)
+            .any(|r| r.pressed == pressed && &r.input == input)
+    }
+}
@@ -0,0 +1,364 @@
+use crate::mouse::MouseQuery;
+use crate::pointer::PointerId;
+use crate::touch::TouchQuery;
+
+/// The area covered by a hit-test region, in window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Rect { origin: [f64; 2], size: [f64; 2] },
+    Circle { center: [f64; 2], radius: f64 },
+}
+
+impl Shape {
+    /// Returns `true` if the position is inside the shape.
+    pub fn contains(&self, [x, y]: [f64; 2]) -> bool {
+        match *self {
+            Shape::Rect {
+                origin: [ox, oy],
+                size: [w, h],
+            } => x >= ox && x < ox + w && y >= oy && y < oy + h,
+            Shape::Circle {
+                center: [cx, cy],
+                radius,
+            } => (x - cx).powi(2) + (y - cy).powi(2) <= radius * radius,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PointerHit<Id, TouchId> {
+    pointer: PointerId<TouchId>,
+    position: [f64; 2],
+    hovered: Option<Id>,
+    pressed_on: Option<Id>,
+    down: bool,
+    pressed: bool,
+    released: bool,
+}
+
+/// A registry of regions that pointers can hover, press and click, for
+/// immediate-mode UI.
+///
+/// Each frame, call `begin_frame`, submit regions with `region` (later
+/// regions are on top of earlier ones), then call `update_mouse` and
+/// `update_touch` before querying. Only the topmost region under a pointer
+/// is hit.
+///
+/// Pressing a pointer inside a region captures it: until the pointer is
+/// released, no other region can be hovered or pressed by it, and the
+/// capturing region sees its moves and final release wherever they happen.
+#[derive(Debug, Clone)]
+pub struct HitRegions<Id, TouchId = u64> {
+    regions: Vec<(Id, Shape)>,
+    pointers: Vec<PointerHit<Id, TouchId>>,
+}
+
+impl<Id, TouchId> Default for HitRegions<Id, TouchId>
+where
+    Id: Clone + PartialEq,
+    TouchId: Clone + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id, TouchId> HitRegions<Id, TouchId>
+where
+    Id: Clone + PartialEq,
+    TouchId: Clone + PartialEq,
+{
+    pub fn new() -> Self {
+        HitRegions {
+            regions: Vec::new(),
+            pointers: Vec::new(),
+        }
+    }
+
+    /// Forget last frame's regions and per-frame pointer state. Presses
+    /// that are still held keep track of the region they started on.
+    pub fn begin_frame(&mut self) -> &mut Self {
+        self.regions.clear();
+        self.pointers
+            .retain(|p| p.down || matches!(p.pointer, PointerId::Mouse));
+        for pointer in &mut self.pointers {
+            if !pointer.down {
+                pointer.pressed_on = None;
+            }
+            pointer.pressed = false;
+            pointer.released = false;
+        }
+        self
+    }
+
+    /// Submit a region for this frame.
+    pub fn region(&mut self, id: Id, shape: Shape) -> &mut Self {
+        self.regions.push((id, shape));
+        self
+    }
+
+    /// Returns the topmost region containing the position, if any.
+    pub fn hit(&self, position: [f64; 2]) -> Option<&Id> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(_, shape)| shape.contains(position))
+            .map(|(id, _)| id)
+    }
+
+    /// Update the mouse pointer, using `button` as its primary button.
+    ///
+    /// While the pointer is captured, it keeps being tracked outside the
+    /// window, so a drag can end there.
+    pub fn update_mouse<M>(&mut self, mouse: &M, button: &M::Button) -> &mut Self
+    where
+        M: MouseQuery<Coord = f64>,
+    {
+        let position = match self.captor(&PointerId::Mouse) {
+            Some(_) => Some(mouse.position()),
+            None => mouse.position_in_window(),
+        };
+        self.update_pointer(
+            PointerId::Mouse,
+            position,
+            mouse.down(button),
+            mouse.pressed(button),
+            mouse.released(button),
+        );
+        self
+    }
+
+    /// Update a pointer for each active touch.
+    pub fn update_touch<T>(&mut self, touchpad: &T) -> &mut Self
+    where
+        T: TouchQuery<TouchId = TouchId, Coord = f64>,
+    {
+        for touch in touchpad.touches() {
+            self.update_pointer(
+                PointerId::Touch(touch.id.clone()),
+                Some(touch.position),
+                !touch.released,
+                touch.tapped,
+                touch.released,
+            );
+        }
+        self
+    }
+
+    /// Returns `true` if a pointer is over the region.
+    pub fn hovered(&self, id: &Id) -> bool {
+        self.pointers.iter().any(|p| p.hovered.as_ref() == Some(id))
+    }
+
+    /// Returns `true` if a pointer was pressed inside the region this frame.
+    pub fn pressed(&self, id: &Id) -> bool {
+        self.pointers
+            .iter()
+            .any(|p| p.pressed && p.pressed_on.as_ref() == Some(id))
+    }
+
+    /// Returns `true` if a pointer that was pressed inside the region was
+    /// released inside it this frame.
+    pub fn clicked(&self, id: &Id) -> bool {
+        self.pointers.iter().any(|p| {
+            p.released && p.pressed_on.as_ref() == Some(id) && p.hovered.as_ref() == Some(id)
+        })
+    }
+
+    /// Returns the pointer dragging the region, if any: one that was pressed
+    /// inside it and is still held, wherever it is now.
+    pub fn dragging(&self, id: &Id) -> Option<&PointerId<TouchId>> {
+        self.pointers
+            .iter()
+            .find(|p| p.down && p.pressed_on.as_ref() == Some(id))
+            .map(|p| &p.pointer)
+    }
+
+    /// Returns the region that has captured the pointer, if any.
+    pub fn captor(&self, pointer: &PointerId<TouchId>) -> Option<&Id> {
+        self.pointers
+            .iter()
+            .find(|p| &p.pointer == pointer)
+            .and_then(|p| p.pressed_on.as_ref())
+    }
+
+    /// Returns the position of the pointer captured by the region, wherever
+    /// it is, if the region has captured one.
+    pub fn capture_position(&self, id: &Id) -> Option<[f64; 2]> {
+        self.pointers
+            .iter()
+            .find(|p| p.pressed_on.as_ref() == Some(id))
+            .map(|p| p.position)
+    }
+
+    /// Returns `true` if a pointer captured by the region was released this
+    /// frame, wherever the release happened.
+    pub fn capture_released(&self, id: &Id) -> bool {
+        self.pointers
+            .iter()
+            .any(|p| p.released && p.pressed_on.as_ref() == Some(id))
+    }
+
+    /// Release a pointer from its region without a click, for example when
+    /// a drag is cancelled.
+    pub fn release_capture(&mut self, pointer: &PointerId<TouchId>) -> &mut Self {
+        if let Some(state) = self.pointers.iter_mut().find(|p| &p.pointer == pointer) {
+            state.pressed_on = None;
+        }
+        self
+    }
+
+    /// Returns the last known position of a pointer.
+    pub fn position(&self, pointer: &PointerId<TouchId>) -> Option<[f64; 2]> {
+        self.pointers
+            .iter()
+            .find(|p| &p.pointer == pointer)
+            .map(|p| p.position)
+    }
+
+    fn update_pointer(
+        &mut self,
+        pointer: PointerId<TouchId>,
+        position: Option<[f64; 2]>,
+        down: bool,
+        pressed: bool,
+        released: bool,
+    ) {
+        let hovered = position.and_then(|p| self.hit(p)).cloned();
+        let index = match self.pointers.iter().position(|p| p.pointer == pointer) {
+            Some(index) => index,
+            None => {
+                self.pointers.push(PointerHit {
+                    pointer,
+                    position: position.unwrap_or_default(),
+                    hovered: None,
+                    pressed_on: None,
+                    down: false,
+                    pressed: false,
+                    released: false,
+                });
+                self.pointers.len() - 1
+            }
+        };
+
+        let state = &mut self.pointers[index];
+        if let Some(position) = position {
+            state.position = position;
+        }
+        let hovered = match (&state.pressed_on, pressed) {
+            (Some(captor), false) => hovered.filter(|h| h == captor),
+            _ => hovered,
+        };
+        if pressed {
+            state.pressed_on = hovered.clone();
+        }
+        state.hovered = hovered;
+        state.down = down;
+        state.pressed |= pressed;
+        state.released |= released;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Mouse, MouseInterface, TouchInterface, Touchpad, touch::TouchPhase};
+
+    fn button() -> Shape {
+        Shape::Rect {
+            origin: [0.0, 0.0],
+            size: [10.0, 10.0],
+        }
+    }
+
+    #[test]
+    fn click_requires_press_and_release_inside() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        let mut regions: HitRegions<&str> = HitRegions::new();
+
+        mouse.move_to([5.0, 5.0]).press(0);
+        regions.region("ok", button()).update_mouse(&mouse, &0);
+        assert!(regions.hovered(&"ok") && regions.pressed(&"ok"));
+
+        mouse.clear_presses();
+        mouse.move_to([50.0, 5.0]);
+        regions.begin_frame();
+        regions.region("ok", button()).update_mouse(&mouse, &0);
+        assert_eq!(regions.dragging(&"ok"), Some(&PointerId::Mouse));
+        assert!(!regions.hovered(&"ok"));
+
+        mouse.clear_presses();
+        mouse.move_to([5.0, 5.0]).release(0);
+        regions.begin_frame();
+        regions.region("ok", button()).update_mouse(&mouse, &0);
+        assert!(regions.clicked(&"ok"));
+    }
+
+    #[test]
+    fn captured_pointer_ignores_other_regions() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        let mut regions: HitRegions<&str> = HitRegions::new();
+        let other = Shape::Rect {
+            origin: [20.0, 0.0],
+            size: [10.0, 10.0],
+        };
+
+        mouse.move_to([5.0, 5.0]).press(0);
+        regions
+            .region("slider", button())
+            .region("other", other)
+            .update_mouse(&mouse, &0);
+
+        mouse.clear_presses();
+        mouse.move_to([25.0, 5.0]).release(0);
+        regions.begin_frame();
+        regions
+            .region("slider", button())
+            .region("other", other)
+            .update_mouse(&mouse, &0);
+        assert!(!regions.hovered(&"other"));
+        assert!(!regions.clicked(&"slider"));
+        assert!(regions.capture_released(&"slider"));
+        assert_eq!(regions.capture_position(&"slider"), Some([25.0, 5.0]));
+
+        regions.begin_frame();
+        regions.region("other", other).update_mouse(&mouse, &0);
+        assert!(regions.hovered(&"other"));
+        assert_eq!(regions.captor(&PointerId::Mouse), None);
+    }
+
+    #[test]
+    fn captured_pointer_is_tracked_outside_the_window() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        let mut regions: HitRegions<&str> = HitRegions::new();
+
+        mouse.move_to([5.0, 5.0]).press(0);
+        regions.region("slider", button()).update_mouse(&mouse, &0);
+
+        mouse.clear_presses();
+        mouse.leave_window().move_to([-20.0, 5.0]).release(0);
+        regions.begin_frame();
+        regions.region("slider", button()).update_mouse(&mouse, &0);
+        assert!(regions.capture_released(&"slider"));
+        assert_eq!(regions.capture_position(&"slider"), Some([-20.0, 5.0]));
+    }
+
+    #[test]
+    fn topmost_region_is_hit_by_touch() {
+        let mut touchpad: Touchpad<u64, f64> = Touchpad::new();
+        let mut regions: HitRegions<&str> = HitRegions::new();
+        touchpad.touch_event(1u64, [5.0, 5.0], TouchPhase::Start);
+        regions
+            .region("panel", button())
+            .region(
+                "knob",
+                Shape::Circle {
+                    center: [5.0, 5.0],
+                    radius: 2.0,
+                },
+            )
+            .update_touch(&touchpad);
+        assert!(regions.pressed(&"knob"));
+        assert!(!regions.hovered(&"panel"));
+    }
+}
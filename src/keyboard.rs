@@ -1,11 +1,17 @@
 use smallvec::SmallVec;
 use smol_str::{SmolStr, SmolStrBuilder};
 
+use std::time::Duration;
+
 use crate::Event;
+use crate::events::{EventLog, KeyboardEvent};
+use crate::history::{History, Within};
+use crate::text::TextCommand;
 
 // TODO: All these traits should take values by reference :')
-/// A trait for objects that can represent the state of a keyboard.
-pub trait KeyboardInterface {
+/// A trait for reading the state of a keyboard. This is implemented by
+/// every `KeyboardInterface`, and by read-only views such as `LayerKeyboard`.
+pub trait KeyboardQuery {
     /// A type representing a key on a keyboard.
     type Key;
 
@@ -24,9 +30,95 @@ pub trait KeyboardInterface {
     /// Returns `true` if the given key was released this frame.
     fn released(&self, key: &Self::Key) -> bool;
 
-    /// Returns any text that has been entered.
+    /// Returns an iterator over all keys currently held down.
+    ///
+    /// The default implementation returns nothing.
+    fn keys_down(&self) -> impl Iterator<Item = &Self::Key> {
+        std::iter::empty()
+    }
+
+    /// Returns an iterator over all keys pressed this frame.
+    ///
+    /// The default implementation returns nothing.
+    fn keys_pressed(&self) -> impl Iterator<Item = &Self::Key> {
+        std::iter::empty()
+    }
+
+    /// Returns an iterator over all keys released this frame.
+    ///
+    /// The default implementation returns nothing.
+    fn keys_released(&self) -> impl Iterator<Item = &Self::Key> {
+        std::iter::empty()
+    }
+
+    /// Returns `true` if the given key was pressed within the given number
+    /// of frames (such as `3`) or amount of time (such as a `Duration`).
+    /// Passing `0` only considers the current frame.
+    ///
+    /// This is useful for input buffering, where a press made slightly too
+    /// early should still count. The default implementation only considers
+    /// the current frame.
+    fn pressed_within<W: Into<Within>>(&self, key: &Self::Key, within: W) -> bool {
+        let _ = within;
+        self.pressed(key)
+    }
+
+    /// Returns `true` if the given key was released within the given number
+    /// of frames or amount of time. Passing `0` only considers the current
+    /// frame.
+    ///
+    /// The default implementation only considers the current frame.
+    fn released_within<W: Into<Within>>(&self, key: &Self::Key, within: W) -> bool {
+        let _ = within;
+        self.released(key)
+    }
+
+    /// Returns any text that has been entered. Control characters (such as
+    /// backspace) are not included; see `text_commands` instead.
     fn text(&self) -> &str;
 
+    /// Returns an iterator over the text editing commands triggered this
+    /// frame, in the order they were triggered.
+    ///
+    /// The default implementation returns nothing.
+    fn text_commands(&self) -> impl Iterator<Item = &TextCommand> {
+        std::iter::empty()
+    }
+
+    /// Returns an iterator over this frame's keyboard events, in the order
+    /// they were received. This is empty unless event logging is enabled.
+    ///
+    /// The default implementation returns nothing.
+    fn events(&self) -> impl Iterator<Item = &KeyboardEvent<Self::Key>> {
+        std::iter::empty()
+    }
+
+    /// Returns the text currently being composed with an input method, if
+    /// any. This text has not been committed, so is not part of `text`.
+    ///
+    /// The default implementation never reports composition.
+    fn preedit(&self) -> Option<&Preedit> {
+        None
+    }
+
+    /// Returns an iterator over text committed by an input method this
+    /// frame. Committed text is also included in `text`.
+    ///
+    /// The default implementation returns nothing.
+    fn ime_commits(&self) -> impl Iterator<Item = &str> {
+        std::iter::empty()
+    }
+
+    /// Returns `true` if an input method is currently enabled.
+    ///
+    /// The default implementation returns `false`.
+    fn ime_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// A trait for objects that can represent the state of a keyboard.
+pub trait KeyboardInterface: KeyboardQuery {
     /// Clears the pressed state of held buttons. Should be called at end of frame.
     fn clear_presses(&mut self) -> &mut Self;
 
@@ -36,6 +128,21 @@ pub trait KeyboardInterface {
     /// Register that a key was released.
     fn release(&mut self, key: Self::Key) -> &mut Self;
 
+    /// Release every held key, for example when the window loses focus and
+    /// the real releases will never arrive.
+    ///
+    /// The default implementation releases each key in `keys_down`.
+    fn release_all(&mut self) -> &mut Self
+    where
+        Self::Key: Clone,
+    {
+        let held: Vec<_> = self.keys_down().cloned().collect();
+        for key in held {
+            self.release(key);
+        }
+        self
+    }
+
     /// Register that the current state of the modifier keys has changed.
     fn set_modifiers(&mut self, modifiers: Self::Mods) -> &mut Self;
 
@@ -45,6 +152,39 @@ pub trait KeyboardInterface {
     /// Register that a character of text was input.
     fn receive_char(&mut self, ch: char) -> &mut Self;
 
+    /// Register that a text editing command was triggered.
+    ///
+    /// The default implementation does nothing.
+    fn push_text_command(&mut self, command: TextCommand) -> &mut Self {
+        let _ = command;
+        self
+    }
+
+    /// Register that the input method's composition text has changed. An
+    /// empty `text` means composition has ended.
+    ///
+    /// The default implementation ignores composition.
+    fn set_preedit<S: AsRef<str>>(&mut self, text: S, cursor: Option<(usize, usize)>) -> &mut Self {
+        let _ = (text, cursor);
+        self
+    }
+
+    /// Register that an input method committed some text. This also clears
+    /// the composition text.
+    ///
+    /// The default implementation treats the text as ordinary text input.
+    fn commit_text<S: AsRef<str>>(&mut self, text: S) -> &mut Self {
+        self.receive_text(text)
+    }
+
+    /// Register that an input method was enabled or disabled.
+    ///
+    /// The default implementation does nothing.
+    fn set_ime_enabled(&mut self, enabled: bool) -> &mut Self {
+        let _ = enabled;
+        self
+    }
+
     /// Convenience method for handling events. The type of event, `E`, will
     /// vary depending on the windowing library being used.
     fn handle_event<E: Event<Self>>(&mut self, event: &E) -> &mut Self {
@@ -55,7 +195,7 @@ pub trait KeyboardInterface {
 
 /// The current state of the modifier keys. You can use this if the windowing
 /// library you are using doesn't have an equivalent.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Modifiers {
     pub ctrl: bool,
     pub alt: bool,
@@ -63,6 +203,31 @@ pub struct Modifiers {
     pub logo: bool,
 }
 
+/// Text being composed with an input method (IME), which has not yet been
+/// committed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Preedit {
+    /// The text being composed.
+    pub text: SmolStr,
+
+    /// The byte range of the cursor (or selection) within `text`, if the
+    /// input method provided one.
+    pub cursor: Option<(usize, usize)>,
+}
+
+/// A trait for types that describe which modifier keys are held, allowing
+/// different windowing libraries' modifier types to be compared uniformly.
+pub trait ModifierState {
+    /// Convert to the common `Modifiers` representation.
+    fn to_modifiers(&self) -> Modifiers;
+}
+
+impl ModifierState for Modifiers {
+    fn to_modifiers(&self) -> Modifiers {
+        *self
+    }
+}
+
 /// A structure representing the current state of a keyboard.
 #[derive(Debug, Clone)]
 pub struct Keyboard<Key, Mods>
@@ -79,6 +244,12 @@ where
     keys_released: SmallVec<[Key; 8]>,
     text_buffer_builder: SmolStrBuilder,
     text_buffer: SmolStr,
+    text_commands: SmallVec<[TextCommand; 4]>,
+    preedit: Option<Preedit>,
+    ime_commits: SmallVec<[SmolStr; 1]>,
+    ime_enabled: bool,
+    history: History<Key>,
+    events: EventLog<KeyboardEvent<Key>>,
 }
 
 impl<Key, Mods> Keyboard<Key, Mods>
@@ -93,8 +264,36 @@ where
             keys_released: Default::default(),
             text_buffer_builder: Default::default(),
             text_buffer: Default::default(),
+            text_commands: Default::default(),
+            preedit: None,
+            ime_commits: Default::default(),
+            ime_enabled: false,
+            history: Default::default(),
+            events: Default::default(),
         }
     }
+
+    /// Enable or disable recording an ordered log of each frame's events,
+    /// available through `events`.
+    pub fn set_event_log(&mut self, enabled: bool) -> &mut Self {
+        self.events.set_enabled(enabled);
+        self
+    }
+
+    /// Set how many frames of presses and releases are remembered for
+    /// `pressed_within` and `released_within`. The default is 60.
+    pub fn set_history_frames(&mut self, frames: u32) -> &mut Self {
+        self.history.set_max_age(frames);
+        self
+    }
+
+    /// Set the duration of the current frame, so that `pressed_within` and
+    /// `released_within` can look back by time. By default, frames are
+    /// assumed to last 1/60 of a second.
+    pub fn set_frame_duration(&mut self, duration: Duration) -> &mut Self {
+        self.history.set_frame_duration(duration);
+        self
+    }
 }
 
 impl<Key, Mods> Default for Keyboard<Key, Mods>
@@ -106,7 +305,7 @@ where
     }
 }
 
-impl<K, M> KeyboardInterface for Keyboard<K, M>
+impl<K, M> KeyboardQuery for Keyboard<K, M>
 where
     K: Clone + PartialEq,
 {
@@ -129,15 +328,64 @@ where
         self.keys_released.iter().any(|k| k == key)
     }
 
+    fn keys_down(&self) -> impl Iterator<Item = &Self::Key> {
+        self.keys_down.iter()
+    }
+
+    fn keys_pressed(&self) -> impl Iterator<Item = &Self::Key> {
+        self.keys_pressed.iter()
+    }
+
+    fn keys_released(&self) -> impl Iterator<Item = &Self::Key> {
+        self.keys_released.iter()
+    }
+
+    fn pressed_within<W: Into<Within>>(&self, key: &Self::Key, within: W) -> bool {
+        self.history.within(key, true, within.into())
+    }
+
+    fn released_within<W: Into<Within>>(&self, key: &Self::Key, within: W) -> bool {
+        self.history.within(key, false, within.into())
+    }
+
     fn text(&self) -> &str {
         &self.text_buffer
     }
 
+    fn text_commands(&self) -> impl Iterator<Item = &TextCommand> {
+        self.text_commands.iter()
+    }
+
+    fn events(&self) -> impl Iterator<Item = &KeyboardEvent<Self::Key>> {
+        self.events.iter()
+    }
+
+    fn preedit(&self) -> Option<&Preedit> {
+        self.preedit.as_ref()
+    }
+
+    fn ime_commits(&self) -> impl Iterator<Item = &str> {
+        self.ime_commits.iter().map(SmolStr::as_str)
+    }
+
+    fn ime_enabled(&self) -> bool {
+        self.ime_enabled
+    }
+}
+
+impl<K, M> KeyboardInterface for Keyboard<K, M>
+where
+    K: Clone + PartialEq,
+{
     fn clear_presses(&mut self) -> &mut Self {
         self.keys_pressed.clear();
         self.keys_released.clear();
         self.text_buffer_builder = SmolStrBuilder::default();
         self.text_buffer = SmolStr::default();
+        self.text_commands.clear();
+        self.ime_commits.clear();
+        self.events.clear();
+        self.history.next_frame();
         self
     }
 
@@ -145,6 +393,10 @@ where
         if !self.down(&key) {
             self.keys_down.push(key.clone());
         }
+        self.history.record(key.clone(), true);
+        if self.events.enabled() {
+            self.events.record(KeyboardEvent::Pressed(key.clone()));
+        }
         if !self.pressed(&key) {
             self.keys_pressed.push(key);
         }
@@ -153,26 +405,88 @@ where
 
     fn release(&mut self, key: Self::Key) -> &mut Self {
         self.keys_down.retain(|k| k != &key);
+        self.history.record(key.clone(), false);
+        if self.events.enabled() {
+            self.events.record(KeyboardEvent::Released(key.clone()));
+        }
         if !self.released(&key) {
             self.keys_released.push(key);
         }
         self
     }
 
+    fn release_all(&mut self) -> &mut Self {
+        for key in std::mem::take(&mut self.keys_down) {
+            self.release(key);
+        }
+        self
+    }
+
     fn set_modifiers(&mut self, modifiers: Self::Mods) -> &mut Self {
         self.modifiers = Some(modifiers);
         self
     }
 
     fn receive_text<S: AsRef<str>>(&mut self, text: S) -> &mut Self {
-        self.text_buffer_builder.push_str(text.as_ref());
-        self.text_buffer = self.text_buffer_builder.finish();
+        let text = text.as_ref();
+        if text.chars().any(char::is_control) {
+            let filtered: SmolStr = text.chars().filter(|ch| !ch.is_control()).collect();
+            return self.receive_text(filtered);
+        }
+        if !text.is_empty() {
+            self.text_buffer_builder.push_str(text);
+            self.text_buffer = self.text_buffer_builder.finish();
+            if self.events.enabled() {
+                self.events.record(KeyboardEvent::Text(text.into()));
+            }
+        }
         self
     }
 
     fn receive_char(&mut self, ch: char) -> &mut Self {
-        self.text_buffer_builder.push(ch);
-        self.text_buffer = self.text_buffer_builder.finish();
+        if !ch.is_control() {
+            self.text_buffer_builder.push(ch);
+            self.text_buffer = self.text_buffer_builder.finish();
+            if self.events.enabled() {
+                let text = ch.encode_utf8(&mut [0; 4]).into();
+                self.events.record(KeyboardEvent::Text(text));
+            }
+        }
+        self
+    }
+
+    fn push_text_command(&mut self, command: TextCommand) -> &mut Self {
+        self.text_commands.push(command);
+        if self.events.enabled() {
+            self.events.record(KeyboardEvent::Command(command));
+        }
+        self
+    }
+
+    fn set_preedit<S: AsRef<str>>(&mut self, text: S, cursor: Option<(usize, usize)>) -> &mut Self {
+        let text = text.as_ref();
+        self.preedit = match text.is_empty() {
+            true => None,
+            false => Some(Preedit {
+                text: text.into(),
+                cursor,
+            }),
+        };
+        self
+    }
+
+    fn commit_text<S: AsRef<str>>(&mut self, text: S) -> &mut Self {
+        let text = text.as_ref();
+        self.preedit = None;
+        self.ime_commits.push(text.into());
+        self.receive_text(text)
+    }
+
+    fn set_ime_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.ime_enabled = enabled;
+        if !enabled {
+            self.preedit = None;
+        }
         self
     }
 }
@@ -249,6 +563,151 @@ mod tests {
         assert!(keyboard.down(&10));
     }
 
+    #[test]
+    fn keys_can_be_listed() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.press(10).press(11).release(10);
+        assert_eq!(keyboard.keys_down().collect::<Vec<_>>(), vec![&11]);
+        assert_eq!(keyboard.keys_pressed().collect::<Vec<_>>(), vec![&10, &11]);
+        assert_eq!(keyboard.keys_released().collect::<Vec<_>>(), vec![&10]);
+    }
+
+    #[test]
+    fn key_pressed_within_remembers_recent_frames() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.press(10);
+        keyboard.clear_presses();
+        keyboard.clear_presses();
+        assert!(!keyboard.pressed(&10));
+        assert!(!keyboard.pressed_within(&10, 1));
+        assert!(keyboard.pressed_within(&10, 2));
+        assert!(!keyboard.released_within(&10, 2));
+    }
+
+    #[test]
+    fn key_released_within_remembers_recent_frames() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.press(10).release(10);
+        keyboard.clear_presses();
+        assert!(keyboard.released_within(&10, 1));
+        assert!(!keyboard.released_within(&11, 1));
+    }
+
+    #[test]
+    fn key_history_is_bounded_by_age() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.set_history_frames(2);
+        keyboard.press(10);
+        keyboard.clear_presses();
+        for _ in 0..100 {
+            keyboard.press(11);
+        }
+        keyboard.clear_presses();
+        assert!(keyboard.pressed_within(&10, 2));
+
+        keyboard.clear_presses();
+        assert!(!keyboard.pressed_within(&10, 3));
+        assert!(keyboard.pressed_within(&11, 2));
+    }
+
+    #[test]
+    fn key_pressed_within_a_duration() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.set_frame_duration(Duration::from_millis(20));
+        keyboard.press(10);
+        for _ in 0..5 {
+            keyboard.clear_presses();
+        }
+        assert!(keyboard.pressed_within(&10, Duration::from_millis(100)));
+        assert!(!keyboard.pressed_within(&10, Duration::from_millis(99)));
+        assert!(!keyboard.pressed_within(&10, 4));
+    }
+
+    #[test]
+    fn control_characters_are_not_text() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard
+            .receive_char('a')
+            .receive_char('\u{8}')
+            .receive_text("b\r\tc")
+            .push_text_command(TextCommand::DeleteBackward);
+        assert_eq!(keyboard.text(), "abc");
+        assert_eq!(
+            keyboard.text_commands().collect::<Vec<_>>(),
+            vec![&TextCommand::DeleteBackward]
+        );
+
+        keyboard.clear_presses();
+        assert_eq!(keyboard.text_commands().count(), 0);
+    }
+
+    #[test]
+    fn release_all_releases_held_keys() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.press(1).press(2);
+        keyboard.clear_presses();
+        keyboard.release_all();
+        assert_eq!(keyboard.keys_down().count(), 0);
+        assert!(keyboard.released(&1));
+        assert!(keyboard.released(&2));
+    }
+
+    #[test]
+    fn event_log_keeps_order_within_a_frame() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.press(1).release(1);
+        assert_eq!(keyboard.events().count(), 0);
+
+        keyboard.set_event_log(true);
+        keyboard
+            .receive_text("ab")
+            .press(2)
+            .push_text_command(TextCommand::DeleteBackward)
+            .release(2)
+            .press(2);
+        assert_eq!(
+            keyboard.events().cloned().collect::<Vec<_>>(),
+            vec![
+                KeyboardEvent::Text("ab".into()),
+                KeyboardEvent::Pressed(2),
+                KeyboardEvent::Command(TextCommand::DeleteBackward),
+                KeyboardEvent::Released(2),
+                KeyboardEvent::Pressed(2),
+            ]
+        );
+
+        keyboard.clear_presses();
+        assert_eq!(keyboard.events().count(), 0);
+    }
+
+    #[test]
+    fn preedit_persists_until_committed() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard
+            .set_ime_enabled(true)
+            .set_preedit("にほ", Some((6, 6)));
+        keyboard.clear_presses();
+        assert_eq!(keyboard.preedit().map(|p| p.text.as_str()), Some("にほ"));
+        assert_eq!(keyboard.text(), "");
+
+        keyboard.commit_text("日本");
+        assert_eq!(keyboard.preedit(), None);
+        assert_eq!(keyboard.text(), "日本");
+        assert_eq!(keyboard.ime_commits().collect::<Vec<_>>(), vec!["日本"]);
+
+        keyboard.clear_presses();
+        assert_eq!(keyboard.ime_commits().count(), 0);
+    }
+
+    #[test]
+    fn disabling_ime_clears_preedit() {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.set_ime_enabled(true).set_preedit("a", None);
+        keyboard.set_ime_enabled(false);
+        assert!(!keyboard.ime_enabled());
+        assert_eq!(keyboard.preedit(), None);
+    }
+
     #[test]
     fn modifiers_empty_by_default() {
         let keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
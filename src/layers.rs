@@ -0,0 +1,547 @@
+use crate::events::{KeyboardEvent, MouseEvent, TouchEvent};
+use crate::history::Within;
+use crate::keyboard::{KeyboardQuery, Preedit};
+use crate::mouse::MouseQuery;
+use crate::text::TextCommand;
+use crate::touch::{Touch, TouchQuery};
+
+#[derive(Debug, Clone)]
+struct Layer<L> {
+    id: L,
+    blocking: bool,
+    consumes_text: bool,
+}
+
+/// A stack of input contexts, such as gameplay, a pause menu and a text
+/// field, where higher layers can consume input so that lower layers don't
+/// see it.
+///
+/// Each frame, handle input from the top layer down: query a layer through
+/// one of the view methods (`keyboard`, `mouse` or `touchpad`), and call the
+/// `consume_*` methods for anything it handled. Views of lower layers then
+/// report consumed inputs as not pressed or held. Call `clear_consumed` at
+/// the end of each frame.
+///
+/// The views borrow the underlying devices, so no input state is copied.
+#[derive(Debug, Clone)]
+pub struct InputLayers<L, Key, Button, TouchId> {
+    layers: Vec<Layer<L>>,
+    consumed_keys: Vec<(usize, Key)>,
+    consumed_buttons: Vec<(usize, Button)>,
+    consumed_touches: Vec<(usize, TouchId)>,
+}
+
+impl<L, Key, Button, TouchId> Default for InputLayers<L, Key, Button, TouchId>
+where
+    L: PartialEq,
+    Key: PartialEq,
+    Button: PartialEq,
+    TouchId: PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L, Key, Button, TouchId> InputLayers<L, Key, Button, TouchId>
+where
+    L: PartialEq,
+    Key: PartialEq,
+    Button: PartialEq,
+    TouchId: PartialEq,
+{
+    pub fn new() -> Self {
+        InputLayers {
+            layers: Vec::new(),
+            consumed_keys: Vec::new(),
+            consumed_buttons: Vec::new(),
+            consumed_touches: Vec::new(),
+        }
+    }
+
+    /// Push a layer on top of the stack. If the layer is already in the
+    /// stack, it is moved to the top.
+    pub fn push(&mut self, layer: L) -> &mut Self {
+        self.remove(&layer);
+        self.layers.push(Layer {
+            id: layer,
+            blocking: false,
+            consumes_text: false,
+        });
+        self
+    }
+
+    /// Remove a layer from the stack.
+    pub fn remove(&mut self, layer: &L) -> &mut Self {
+        if let Some(rank) = self.rank(layer) {
+            self.layers.remove(rank);
+            forget_rank(&mut self.consumed_keys, rank);
+            forget_rank(&mut self.consumed_buttons, rank);
+            forget_rank(&mut self.consumed_touches, rank);
+        }
+        self
+    }
+
+    /// Returns `true` if the layer is in the stack.
+    pub fn contains(&self, layer: &L) -> bool {
+        self.rank(layer).is_some()
+    }
+
+    /// Set whether a layer hides all input from the layers below it, as a
+    /// modal pause menu would. This persists across frames.
+    pub fn set_blocking(&mut self, layer: &L, blocking: bool) -> &mut Self {
+        if let Some(rank) = self.rank(layer) {
+            self.layers[rank].blocking = blocking;
+        }
+        self
+    }
+
+    /// Hide a key from the layers below `layer` for the rest of this frame.
+    pub fn consume_key(&mut self, layer: &L, key: Key) -> &mut Self {
+        if let Some(rank) = self.rank(layer) {
+            self.consumed_keys.push((rank, key));
+        }
+        self
+    }
+
+    /// Hide a mouse button from the layers below `layer` for the rest of
+    /// this frame.
+    pub fn consume_button(&mut self, layer: &L, button: Button) -> &mut Self {
+        if let Some(rank) = self.rank(layer) {
+            self.consumed_buttons.push((rank, button));
+        }
+        self
+    }
+
+    /// Hide a touch from the layers below `layer` for the rest of this frame.
+    pub fn consume_touch(&mut self, layer: &L, id: TouchId) -> &mut Self {
+        if let Some(rank) = self.rank(layer) {
+            self.consumed_touches.push((rank, id));
+        }
+        self
+    }
+
+    /// Hide this frame's text input and text editing commands from the
+    /// layers below `layer`, as a focused text field would.
+    pub fn consume_text(&mut self, layer: &L) -> &mut Self {
+        if let Some(rank) = self.rank(layer) {
+            self.layers[rank].consumes_text = true;
+        }
+        self
+    }
+
+    /// Forget everything consumed this frame. Should be called at the end of
+    /// each frame.
+    pub fn clear_consumed(&mut self) -> &mut Self {
+        self.consumed_keys.clear();
+        self.consumed_buttons.clear();
+        self.consumed_touches.clear();
+        for layer in &mut self.layers {
+            layer.consumes_text = false;
+        }
+        self
+    }
+
+    /// Returns a view of the keyboard as seen by the given layer. A layer
+    /// that isn't in the stack sees all input.
+    pub fn keyboard<'a, K>(&'a self, layer: &L, keyboard: &'a K) -> LayerKeyboard<'a, K>
+    where
+        K: KeyboardQuery<Key = Key>,
+    {
+        let rank = self.view_rank(layer);
+        LayerKeyboard {
+            keyboard,
+            rank,
+            blocked: self.blocked(rank),
+            text_consumed: self.layers.iter().skip(rank + 1).any(|l| l.consumes_text),
+            consumed: &self.consumed_keys,
+        }
+    }
+
+    /// Returns a view of the mouse as seen by the given layer. A layer that
+    /// isn't in the stack sees all input.
+    pub fn mouse<'a, M>(&'a self, layer: &L, mouse: &'a M) -> LayerMouse<'a, M>
+    where
+        M: MouseQuery<Button = Button>,
+    {
+        let rank = self.view_rank(layer);
+        LayerMouse {
+            mouse,
+            rank,
+            blocked: self.blocked(rank),
+            consumed: &self.consumed_buttons,
+        }
+    }
+
+    /// Returns a view of the touchpad as seen by the given layer. A layer
+    /// that isn't in the stack sees all input.
+    pub fn touchpad<'a, T>(&'a self, layer: &L, touchpad: &'a T) -> LayerTouchpad<'a, T>
+    where
+        T: TouchQuery<TouchId = TouchId>,
+    {
+        let rank = self.view_rank(layer);
+        LayerTouchpad {
+            touchpad,
+            rank,
+            blocked: self.blocked(rank),
+            consumed: &self.consumed_touches,
+        }
+    }
+
+    fn rank(&self, layer: &L) -> Option<usize> {
+        self.layers.iter().position(|l| &l.id == layer)
+    }
+
+    fn view_rank(&self, layer: &L) -> usize {
+        self.rank(layer).unwrap_or(self.layers.len())
+    }
+
+    fn blocked(&self, rank: usize) -> bool {
+        self.layers.iter().skip(rank + 1).any(|l| l.blocking)
+    }
+}
+
+/// Drop inputs consumed by the layer at `rank`, and shift the ranks of the
+/// layers above it down to match.
+fn forget_rank<T>(consumed: &mut Vec<(usize, T)>, rank: usize) {
+    consumed.retain(|(r, _)| *r != rank);
+    for (r, _) in consumed {
+        if *r > rank {
+            *r -= 1;
+        }
+    }
+}
+
+fn consumed_above<T: PartialEq>(consumed: &[(usize, T)], rank: usize, input: &T) -> bool {
+    consumed.iter().any(|(r, i)| *r > rank && i == input)
+}
+
+/// A view of a keyboard from one of the layers in `InputLayers`.
+///
+/// Keys consumed by a higher layer are reported as not held, pressed or
+/// released, and text is hidden if a higher layer consumed it. Views are
+/// read-only, so they only implement `KeyboardQuery`.
+#[derive(Debug)]
+pub struct LayerKeyboard<'a, K: KeyboardQuery> {
+    keyboard: &'a K,
+    rank: usize,
+    blocked: bool,
+    text_consumed: bool,
+    consumed: &'a [(usize, K::Key)],
+}
+
+impl<K> LayerKeyboard<'_, K>
+where
+    K: KeyboardQuery,
+    K::Key: PartialEq,
+{
+    fn visible(&self, key: &K::Key) -> bool {
+        !self.blocked && !consumed_above(self.consumed, self.rank, key)
+    }
+
+    fn text_visible(&self) -> bool {
+        !(self.blocked || self.text_consumed)
+    }
+}
+
+impl<K> KeyboardQuery for LayerKeyboard<'_, K>
+where
+    K: KeyboardQuery,
+    K::Key: PartialEq,
+{
+    type Key = K::Key;
+    type Mods = K::Mods;
+
+    fn modifiers(&self) -> Option<&K::Mods> {
+        self.keyboard.modifiers()
+    }
+
+    fn down(&self, key: &K::Key) -> bool {
+        self.visible(key) && self.keyboard.down(key)
+    }
+
+    fn pressed(&self, key: &K::Key) -> bool {
+        self.visible(key) && self.keyboard.pressed(key)
+    }
+
+    fn released(&self, key: &K::Key) -> bool {
+        self.visible(key) && self.keyboard.released(key)
+    }
+
+    fn keys_down(&self) -> impl Iterator<Item = &K::Key> {
+        self.keyboard.keys_down().filter(|k| self.visible(k))
+    }
+
+    fn keys_pressed(&self) -> impl Iterator<Item = &K::Key> {
+        self.keyboard.keys_pressed().filter(|k| self.visible(k))
+    }
+
+    fn keys_released(&self) -> impl Iterator<Item = &K::Key> {
+        self.keyboard.keys_released().filter(|k| self.visible(k))
+    }
+
+    fn pressed_within<W: Into<Within>>(&self, key: &K::Key, within: W) -> bool {
+        self.visible(key) && self.keyboard.pressed_within(key, within)
+    }
+
+    fn released_within<W: Into<Within>>(&self, key: &K::Key, within: W) -> bool {
+        self.visible(key) && self.keyboard.released_within(key, within)
+    }
+
+    fn text(&self) -> &str {
+        match self.text_visible() {
+            true => self.keyboard.text(),
+            false => "",
+        }
+    }
+
+    fn text_commands(&self) -> impl Iterator<Item = &TextCommand> {
+        let visible = self.text_visible();
+        self.keyboard.text_commands().filter(move |_| visible)
+    }
+
+    fn events(&self) -> impl Iterator<Item = &KeyboardEvent<K::Key>> {
+        self.keyboard.events().filter(|event| match event {
+            KeyboardEvent::Pressed(key) | KeyboardEvent::Released(key) => self.visible(key),
+            KeyboardEvent::Text(_) | KeyboardEvent::Command(_) => self.text_visible(),
+        })
+    }
+
+    fn preedit(&self) -> Option<&Preedit> {
+        self.keyboard.preedit().filter(|_| self.text_visible())
+    }
+
+    fn ime_commits(&self) -> impl Iterator<Item = &str> {
+        let visible = self.text_visible();
+        self.keyboard.ime_commits().filter(move |_| visible)
+    }
+
+    fn ime_enabled(&self) -> bool {
+        self.keyboard.ime_enabled()
+    }
+}
+
+/// A view of a mouse from one of the layers in `InputLayers`.
+///
+/// Buttons consumed by a higher layer are reported as not held, pressed or
+/// released. The pointer's position and motion are never consumed. Views
+/// are read-only, so they only implement `MouseQuery`.
+#[derive(Debug)]
+pub struct LayerMouse<'a, M: MouseQuery> {
+    mouse: &'a M,
+    rank: usize,
+    blocked: bool,
+    consumed: &'a [(usize, M::Button)],
+}
+
+impl<M> LayerMouse<'_, M>
+where
+    M: MouseQuery,
+    M::Button: PartialEq,
+{
+    fn visible(&self, button: &M::Button) -> bool {
+        !self.blocked && !consumed_above(self.consumed, self.rank, button)
+    }
+}
+
+impl<M> MouseQuery for LayerMouse<'_, M>
+where
+    M: MouseQuery,
+    M::Button: PartialEq,
+{
+    type Button = M::Button;
+    type Coord = M::Coord;
+
+    fn position(&self) -> [M::Coord; 2] {
+        self.mouse.position()
+    }
+
+    fn position_in_window(&self) -> Option<[M::Coord; 2]> {
+        self.mouse.position_in_window()
+    }
+
+    fn inside_window(&self) -> bool {
+        self.mouse.inside_window()
+    }
+
+    fn entered_window(&self) -> bool {
+        self.mouse.entered_window()
+    }
+
+    fn left_window(&self) -> bool {
+        self.mouse.left_window()
+    }
+
+    fn motion_delta(&self) -> [M::Coord; 2]
+    where
+        M::Coord: Default,
+    {
+        self.mouse.motion_delta()
+    }
+
+    fn down(&self, button: &M::Button) -> bool {
+        self.visible(button) && self.mouse.down(button)
+    }
+
+    fn pressed(&self, button: &M::Button) -> bool {
+        self.visible(button) && self.mouse.pressed(button)
+    }
+
+    fn released(&self, button: &M::Button) -> bool {
+        self.visible(button) && self.mouse.released(button)
+    }
+
+    fn buttons_down(&self) -> impl Iterator<Item = &M::Button> {
+        self.mouse.buttons_down().filter(|b| self.visible(b))
+    }
+
+    fn buttons_pressed(&self) -> impl Iterator<Item = &M::Button> {
+        self.mouse.buttons_pressed().filter(|b| self.visible(b))
+    }
+
+    fn buttons_released(&self) -> impl Iterator<Item = &M::Button> {
+        self.mouse.buttons_released().filter(|b| self.visible(b))
+    }
+
+    fn pressed_within<W: Into<Within>>(&self, button: &M::Button, within: W) -> bool {
+        self.visible(button) && self.mouse.pressed_within(button, within)
+    }
+
+    fn released_within<W: Into<Within>>(&self, button: &M::Button, within: W) -> bool {
+        self.visible(button) && self.mouse.released_within(button, within)
+    }
+
+    fn events(&self) -> impl Iterator<Item = &MouseEvent<M::Button, M::Coord>> {
+        self.mouse.events().filter(|event| match event {
+            MouseEvent::Pressed(button) | MouseEvent::Released(button) => self.visible(button),
+            _ => true,
+        })
+    }
+}
+
+/// A view of a touchpad from one of the layers in `InputLayers`.
+///
+/// Touches consumed by a higher layer are left out. Views are read-only, so
+/// they only implement `TouchQuery`.
+#[derive(Debug)]
+pub struct LayerTouchpad<'a, T: TouchQuery> {
+    touchpad: &'a T,
+    rank: usize,
+    blocked: bool,
+    consumed: &'a [(usize, T::TouchId)],
+}
+
+impl<T: TouchQuery> LayerTouchpad<'_, T> {
+    fn visible(&self, id: &T::TouchId) -> bool {
+        !self.blocked && !consumed_above(self.consumed, self.rank, id)
+    }
+}
+
+impl<T: TouchQuery> TouchQuery for LayerTouchpad<'_, T> {
+    type TouchId = T::TouchId;
+    type Coord = T::Coord;
+
+    fn first_touch(&self) -> Option<&Touch<T::TouchId, T::Coord>> {
+        self.touches().next()
+    }
+
+    fn touch<I: AsRef<T::TouchId>>(&self, id: I) -> Option<&Touch<T::TouchId, T::Coord>> {
+        self.touches().find(|t| &t.id == id.as_ref())
+    }
+
+    fn touches(&self) -> impl Iterator<Item = &Touch<T::TouchId, T::Coord>> {
+        self.touchpad.touches().filter(|t| self.visible(&t.id))
+    }
+
+    fn events(&self) -> impl Iterator<Item = &TouchEvent<T::TouchId, T::Coord>> {
+        self.touchpad.events().filter(|e| self.visible(&e.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::touch::TouchPhase;
+    use crate::{
+        Keyboard, KeyboardInterface, Modifiers, Mouse, MouseInterface, TouchInterface, Touchpad,
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum Context {
+        Gameplay,
+        Menu,
+    }
+
+    type TestLayers = InputLayers<Context, char, u8, u64>;
+
+    #[test]
+    fn consumed_keys_are_hidden_from_lower_layers() {
+        let mut keyboard: Keyboard<char, Modifiers> = Keyboard::new();
+        keyboard.press('w').press('q').receive_text("wq");
+
+        let mut layers = TestLayers::new();
+        layers.push(Context::Gameplay).push(Context::Menu);
+
+        assert!(layers.keyboard(&Context::Menu, &keyboard).pressed(&'q'));
+        layers
+            .consume_key(&Context::Menu, 'q')
+            .consume_text(&Context::Menu);
+
+        let gameplay = layers.keyboard(&Context::Gameplay, &keyboard);
+        assert!(!gameplay.pressed(&'q'));
+        assert!(!gameplay.down(&'q'));
+        assert!(gameplay.pressed(&'w'));
+        assert_eq!(gameplay.text(), "");
+        assert!(layers.keyboard(&Context::Menu, &keyboard).pressed(&'q'));
+
+        layers.clear_consumed();
+        assert!(layers.keyboard(&Context::Gameplay, &keyboard).pressed(&'q'));
+    }
+
+    #[test]
+    fn blocking_layer_hides_everything_below() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        mouse.press(0);
+
+        let mut layers = TestLayers::new();
+        layers.push(Context::Gameplay).push(Context::Menu);
+        layers.set_blocking(&Context::Menu, true);
+
+        assert!(!layers.mouse(&Context::Gameplay, &mouse).pressed(&0));
+        assert!(layers.mouse(&Context::Menu, &mouse).pressed(&0));
+
+        layers.remove(&Context::Menu);
+        assert!(layers.mouse(&Context::Gameplay, &mouse).pressed(&0));
+    }
+
+    #[test]
+    fn views_implement_query_traits() {
+        fn any_pressed<K: KeyboardQuery>(keyboard: &K) -> bool {
+            keyboard.keys_pressed().next().is_some()
+        }
+
+        let mut keyboard: Keyboard<char, Modifiers> = Keyboard::new();
+        let mut touchpad: Touchpad<u64, f64> = Touchpad::new();
+        keyboard.press('q');
+        touchpad.touch_event(1u64, [0.0, 0.0], TouchPhase::Start);
+
+        let mut layers = TestLayers::new();
+        layers.push(Context::Gameplay).push(Context::Menu);
+        layers
+            .consume_key(&Context::Menu, 'q')
+            .consume_touch(&Context::Menu, 1);
+
+        assert!(any_pressed(&layers.keyboard(&Context::Menu, &keyboard)));
+        assert!(!any_pressed(
+            &layers.keyboard(&Context::Gameplay, &keyboard)
+        ));
+        let menu = layers.touchpad(&Context::Menu, &touchpad);
+        assert_eq!(menu.touches().count(), 1);
+        assert!(
+            layers
+                .touchpad(&Context::Gameplay, &touchpad)
+                .first_touch()
+                .is_none()
+        );
+    }
+}
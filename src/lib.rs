@@ -59,19 +59,56 @@ pub mod support;
 
 /// Prelude that exports traits for supported input device types.
 pub mod prelude {
-    pub use crate::keyboard::KeyboardInterface;
-    pub use crate::mouse::MouseInterface;
-    pub use crate::touch::TouchInterface;
+    pub use crate::keyboard::{KeyboardInterface, KeyboardQuery};
+    pub use crate::mouse::{MouseInterface, MouseQuery};
+    pub use crate::pointer::PointerInterface;
+    pub use crate::touch::{TouchInterface, TouchQuery};
 }
 
+mod bindings;
+mod capture;
+mod conflicts;
+mod events;
+mod history;
+mod hit;
 mod keyboard;
+mod layers;
+mod look;
 mod mouse;
+mod names;
+mod players;
+mod pointer;
+mod router;
+mod sequence;
+mod shortcut;
+mod text;
 mod touch;
+mod transform;
 
 // TODO: Should keys/buttons etc. be passed by ref? Probably...
-pub use crate::keyboard::{Keyboard, KeyboardInterface, Modifiers};
-pub use crate::mouse::{Mouse, MouseInterface};
-pub use crate::touch::{Touch, TouchInterface, Touchpad};
+pub use crate::bindings::{Binding, BindingError, BindingErrorKind, Bindings, ParseBindingsError};
+pub use crate::capture::{Capture, CaptureResult};
+pub use crate::conflicts::{BindingRef, Conflict, ConflictAnalyzer, ConflictKind};
+pub use crate::events::{KeyboardEvent, MouseEvent, TouchEvent};
+pub use crate::history::Within;
+pub use crate::hit::{HitRegions, Shape};
+pub use crate::keyboard::{
+    Keyboard, KeyboardInterface, KeyboardQuery, ModifierState, Modifiers, Preedit,
+};
+pub use crate::layers::{InputLayers, LayerKeyboard, LayerMouse, LayerTouchpad};
+pub use crate::look::{Acceleration, MouseLook};
+pub use crate::mouse::{Mouse, MouseInterface, MouseQuery};
+pub use crate::names::{KeyName, KeyNames, Named, UnknownKeyName};
+pub use crate::players::{InputSource, PlayerActions, PlayerEvent, PlayerSlots};
+pub use crate::pointer::{
+    MousePointer, Pointer, PointerId, PointerInterface, PointerKind, TouchPointers,
+};
+pub use crate::router::Router;
+pub use crate::sequence::{Sequence, SequenceMatcher, Step};
+pub use crate::shortcut::{ModifierMatch, ParseShortcutError, Platform, Shortcut, ShortcutDisplay};
+pub use crate::text::{EditKey, TextCommand, TextMotion};
+pub use crate::touch::{Touch, TouchInterface, TouchQuery, Touchpad};
+pub use crate::transform::{Transform, TransformedMouse, TransformedTouchpad, Viewport};
 
 /// A trait for events that can modify input state.
 pub trait Event<Handler: ?Sized> {
@@ -0,0 +1,173 @@
+use crate::mouse::MouseQuery;
+
+/// How relative mouse motion is scaled by its speed before sensitivity is
+/// applied. Speed is the length of the motion delta in one frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Acceleration {
+    /// Motion is used as-is.
+    #[default]
+    None,
+
+    /// Motion is scaled by `1 + gain * speed`, up to `max`.
+    Linear { gain: f64, max: f64 },
+
+    /// Motion is scaled by `speed ^ (exponent - 1)`, so its length becomes
+    /// `speed ^ exponent`. An exponent above 1 makes fast flicks go further.
+    Power { exponent: f64 },
+}
+
+impl Acceleration {
+    /// Returns the factor to scale a delta of the given length by.
+    pub fn factor(&self, speed: f64) -> f64 {
+        match *self {
+            Acceleration::None => 1.0,
+            Acceleration::Linear { gain, max } => f64::min(1.0 + gain * speed, max),
+            Acceleration::Power { exponent } if speed > 0.0 => speed.powf(exponent - 1.0),
+            Acceleration::Power { .. } => 0.0,
+        }
+    }
+}
+
+/// Turns relative mouse motion into look input, for first-person cameras.
+///
+/// This works on the device's raw motion (`MouseQuery::motion_delta`),
+/// not on `position`, so it keeps working while the cursor is grabbed or
+/// hidden. Call `update` once per frame.
+///
+/// # Examples
+///
+/// ```rust
+/// use buttons::{Acceleration, MouseLook};
+///
+/// let mut look = MouseLook::new()
+///     .sensitivity(0.5)
+///     .invert_y(true)
+///     .acceleration(Acceleration::None);
+/// assert_eq!(look.process([4.0, 2.0]), [2.0, -1.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MouseLook {
+    sensitivity: [f64; 2],
+    acceleration: Acceleration,
+    invert_y: bool,
+    smoothing: f64,
+    output: [f64; 2],
+}
+
+impl Default for MouseLook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MouseLook {
+    pub fn new() -> Self {
+        MouseLook {
+            sensitivity: [1.0, 1.0],
+            acceleration: Acceleration::None,
+            invert_y: false,
+            smoothing: 0.0,
+            output: [0.0, 0.0],
+        }
+    }
+
+    /// Set the sensitivity on both axes.
+    pub fn sensitivity(self, sensitivity: f64) -> Self {
+        self.sensitivity_xy([sensitivity, sensitivity])
+    }
+
+    /// Set separate horizontal and vertical sensitivities.
+    pub fn sensitivity_xy(mut self, sensitivity: [f64; 2]) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    /// Set the acceleration curve.
+    pub fn acceleration(mut self, acceleration: Acceleration) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    /// Make moving the mouse forward look down instead of up.
+    pub fn invert_y(mut self, invert_y: bool) -> Self {
+        self.invert_y = invert_y;
+        self
+    }
+
+    /// Set how much of the previous frame's output is kept, from 0 (no
+    /// smoothing) up to, but not including, 1.
+    pub fn smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 0.99);
+        self
+    }
+
+    /// Returns the most recent look input.
+    pub fn output(&self) -> [f64; 2] {
+        self.output
+    }
+
+    /// Process this frame's relative motion and return the look input.
+    pub fn process(&mut self, [dx, dy]: [f64; 2]) -> [f64; 2] {
+        let factor = self.acceleration.factor(dx.hypot(dy));
+        let dy = if self.invert_y { -dy } else { dy };
+        let target = [
+            dx * factor * self.sensitivity[0],
+            dy * factor * self.sensitivity[1],
+        ];
+        let keep = self.smoothing;
+        self.output = [
+            self.output[0] * keep + target[0] * (1.0 - keep),
+            self.output[1] * keep + target[1] * (1.0 - keep),
+        ];
+        self.output
+    }
+
+    /// Process the mouse's relative motion for this frame.
+    pub fn update<M>(&mut self, mouse: &M) -> [f64; 2]
+    where
+        M: MouseQuery<Coord = f64>,
+    {
+        self.process(mouse.motion_delta())
+    }
+
+    /// Forget smoothed motion, for example after a cutscene or respawn.
+    pub fn reset(&mut self) -> &mut Self {
+        self.output = [0.0, 0.0];
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Mouse, MouseInterface};
+
+    #[test]
+    fn uses_relative_motion_not_position() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        let mut look = MouseLook::new().sensitivity_xy([2.0, 1.0]);
+        mouse.warp_to([100.0, 100.0]);
+        mouse
+            .add_motion_delta([1.0, 3.0])
+            .add_motion_delta([1.0, 0.0]);
+        assert_eq!(look.update(&mouse), [4.0, 3.0]);
+
+        mouse.clear_presses();
+        assert_eq!(look.update(&mouse), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn acceleration_and_smoothing() {
+        let mut look = MouseLook::new().acceleration(Acceleration::Linear {
+            gain: 0.5,
+            max: 2.0,
+        });
+        assert_eq!(look.process([2.0, 0.0]), [4.0, 0.0]);
+        assert_eq!(look.process([10.0, 0.0]), [20.0, 0.0]);
+
+        let mut look = MouseLook::new().smoothing(0.5);
+        assert_eq!(look.process([4.0, 0.0]), [2.0, 0.0]);
+        assert_eq!(look.process([0.0, 0.0]), [1.0, 0.0]);
+        assert_eq!(look.reset().output(), [0.0, 0.0]);
+    }
+}
@@ -1,20 +1,70 @@
 use crate::Event;
+use crate::events::{EventLog, MouseEvent};
+use crate::history::{History, Within};
+use crate::transform::{Transform, TransformedMouse};
 
 use smallvec::SmallVec;
 
+use std::collections::VecDeque;
 use std::ops::Add;
+use std::time::Duration;
 
-/// A trait for objects that can represent the state of a mouse.
-pub trait MouseInterface {
+const DEFAULT_MOTION_SAMPLES: usize = 4;
+
+/// A trait for reading the state of a mouse. This is implemented by every
+/// `MouseInterface`, and by read-only views such as `LayerMouse`.
+pub trait MouseQuery {
     /// A type representing a mouse button.
     type Button;
 
     /// The numeric type used for pointer coordinates.
     type Coord;
 
-    /// Returns the position of the mouse pointer.
+    /// Returns the position of the mouse pointer. When the pointer is
+    /// outside the window, this is the last position inside it.
     fn position(&self) -> [Self::Coord; 2];
 
+    /// Returns the position of the mouse pointer, or `None` if it is outside
+    /// the window.
+    fn position_in_window(&self) -> Option<[Self::Coord; 2]> {
+        match self.inside_window() {
+            true => Some(self.position()),
+            false => None,
+        }
+    }
+
+    /// Returns `true` if the pointer is inside the window. This is assumed
+    /// to be the case until told otherwise.
+    ///
+    /// The default implementation always returns `true`.
+    fn inside_window(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if the pointer entered the window this frame.
+    ///
+    /// The default implementation returns `false`.
+    fn entered_window(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the pointer left the window this frame.
+    ///
+    /// The default implementation returns `false`.
+    fn left_window(&self) -> bool {
+        false
+    }
+
+    /// Returns the total relative motion reported by the device this frame.
+    /// Unlike changes in `position`, this is not limited by the window edges
+    /// or pointer acceleration, so it suits camera controls.
+    fn motion_delta(&self) -> [Self::Coord; 2]
+    where
+        Self::Coord: Default,
+    {
+        Default::default()
+    }
+
     /// Returns `true` if the given button is currently held down.
     fn down(&self, button: &Self::Button) -> bool;
 
@@ -24,21 +74,131 @@ pub trait MouseInterface {
     /// Returns `true` if the given button was released this frame.
     fn released(&self, button: &Self::Button) -> bool;
 
+    /// Returns an iterator over all buttons currently held down.
+    ///
+    /// The default implementation returns nothing.
+    fn buttons_down(&self) -> impl Iterator<Item = &Self::Button> {
+        std::iter::empty()
+    }
+
+    /// Returns an iterator over all buttons pressed this frame.
+    ///
+    /// The default implementation returns nothing.
+    fn buttons_pressed(&self) -> impl Iterator<Item = &Self::Button> {
+        std::iter::empty()
+    }
+
+    /// Returns an iterator over all buttons released this frame.
+    ///
+    /// The default implementation returns nothing.
+    fn buttons_released(&self) -> impl Iterator<Item = &Self::Button> {
+        std::iter::empty()
+    }
+
+    /// Returns `true` if the given button was pressed within the given
+    /// number of frames or amount of time. Passing `0` only considers the
+    /// current frame.
+    ///
+    /// The default implementation only considers the current frame.
+    fn pressed_within<W: Into<Within>>(&self, button: &Self::Button, within: W) -> bool {
+        let _ = within;
+        self.pressed(button)
+    }
+
+    /// Returns `true` if the given button was released within the given
+    /// number of frames or amount of time. Passing `0` only considers the
+    /// current frame.
+    ///
+    /// The default implementation only considers the current frame.
+    fn released_within<W: Into<Within>>(&self, button: &Self::Button, within: W) -> bool {
+        let _ = within;
+        self.released(button)
+    }
+
+    /// Returns an iterator over this frame's mouse events, in the order they
+    /// were received. This is empty unless event logging is enabled.
+    ///
+    /// The default implementation returns nothing.
+    fn events(&self) -> impl Iterator<Item = &MouseEvent<Self::Button, Self::Coord>> {
+        std::iter::empty()
+    }
+
+    /// Returns a view of this mouse with positions converted by `transform`.
+    fn transformed<T>(&self, transform: T) -> TransformedMouse<'_, Self, T>
+    where
+        Self: Sized,
+        T: Transform<Self::Coord>,
+    {
+        TransformedMouse::new(self, transform)
+    }
+}
+
+/// A trait for objects that can represent the state of a mouse.
+pub trait MouseInterface: MouseQuery {
     /// Clears the pressed state of held buttons. Should be called at end of frame.
     fn clear_presses(&mut self) -> &mut Self;
 
     /// Set the position of the mouse to the given value.
+    ///
+    /// This counts as motion for `velocity`, since every cursor event goes
+    /// through it and can't be told apart from a fast flick. Teleports that
+    /// shouldn't count, such as moving the cursor from the application,
+    /// should use `warp_to` instead, which resets velocity.
     fn move_to(&mut self, position: [Self::Coord; 2]) -> &mut Self;
 
     /// Modify the position of the mouse by the given offset.
     fn move_by(&mut self, delta_position: [Self::Coord; 2]) -> &mut Self;
 
+    /// Register relative motion reported by the device.
+    fn add_motion_delta(&mut self, delta: [Self::Coord; 2]) -> &mut Self {
+        let _ = delta;
+        self
+    }
+
+    /// Move the pointer without it counting as motion, for example after
+    /// re-centering a hidden cursor. Any motion history is forgotten, so
+    /// velocity and acceleration are reset.
+    ///
+    /// Without motion history, this is the same as `move_to`.
+    fn warp_to(&mut self, position: [Self::Coord; 2]) -> &mut Self {
+        self.move_to(position)
+    }
+
     /// Register that a button was pressed down.
     fn press(&mut self, button: Self::Button) -> &mut Self;
 
     /// Register that a button was released.
     fn release(&mut self, button: Self::Button) -> &mut Self;
 
+    /// Release every held button, for example when the window loses focus
+    /// and the real releases will never arrive.
+    ///
+    /// The default implementation releases each button in `buttons_down`.
+    fn release_all(&mut self) -> &mut Self
+    where
+        Self::Button: Clone,
+    {
+        let held: Vec<_> = self.buttons_down().cloned().collect();
+        for button in held {
+            self.release(button);
+        }
+        self
+    }
+
+    /// Register that the pointer entered the window.
+    ///
+    /// The default implementation does nothing.
+    fn enter_window(&mut self) -> &mut Self {
+        self
+    }
+
+    /// Register that the pointer left the window.
+    ///
+    /// The default implementation does nothing.
+    fn leave_window(&mut self) -> &mut Self {
+        self
+    }
+
     /// Convenience method for handling events. The type of event, `E`, will
     /// vary depending on the windowing library being used.
     fn handle_event<E: Event<Self>>(&mut self, event: &E) -> &mut Self {
@@ -55,9 +215,18 @@ where
     Coord: Copy + Default + Add<Output = Coord>,
 {
     position: [Coord; 2],
+    motion_delta: [Coord; 2],
+    scale_factor: f64,
+    inside_window: bool,
+    entered_window: bool,
+    left_window: bool,
     buttons_down: SmallVec<[Button; 4]>,
     buttons_pressed: SmallVec<[Button; 4]>,
     buttons_released: SmallVec<[Button; 4]>,
+    history: History<Button>,
+    events: EventLog<MouseEvent<Button, Coord>>,
+    motion: VecDeque<([Coord; 2], Duration)>,
+    motion_samples: usize,
 }
 
 impl<Button, Coord> Default for Mouse<Button, Coord>
@@ -78,10 +247,52 @@ where
     pub fn new() -> Self {
         Mouse {
             position: Default::default(),
+            motion_delta: Default::default(),
+            scale_factor: 1.0,
+            inside_window: true,
+            entered_window: false,
+            left_window: false,
             buttons_down: Default::default(),
             buttons_pressed: Default::default(),
             buttons_released: Default::default(),
+            history: Default::default(),
+            events: Default::default(),
+            motion: VecDeque::new(),
+            motion_samples: DEFAULT_MOTION_SAMPLES,
+        }
+    }
+
+    /// Set how many frames of pointer positions are used for
+    /// `smoothed_velocity`.
+    pub fn set_motion_samples(&mut self, frames: usize) -> &mut Self {
+        self.motion_samples = frames.max(2);
+        while self.motion.len() > self.motion_samples {
+            self.motion.pop_front();
         }
+        self
+    }
+
+    /// Enable or disable recording an ordered log of each frame's events,
+    /// available through `events`.
+    pub fn set_event_log(&mut self, enabled: bool) -> &mut Self {
+        self.events.set_enabled(enabled);
+        self
+    }
+
+    /// Set how many frames of presses and releases are remembered for
+    /// `pressed_within` and `released_within`. The default is 60.
+    pub fn set_history_frames(&mut self, frames: u32) -> &mut Self {
+        self.history.set_max_age(frames);
+        self
+    }
+
+    /// Set the real duration of the current frame, so that `pressed_within`
+    /// and `released_within` can look back by time, and velocities are
+    /// measured per second. By default, frames are assumed to last 1/60 of a
+    /// second.
+    pub fn set_frame_duration(&mut self, duration: Duration) -> &mut Self {
+        self.history.set_frame_duration(duration);
+        self
     }
 
     /// Create the Mouse at a specific pointer position.
@@ -93,7 +304,133 @@ where
     }
 }
 
-impl<B, C> MouseInterface for Mouse<B, C>
+/// Pointer motion over recent frames, for any coordinate type that converts
+/// to `f64`.
+impl<Button, Coord> Mouse<Button, Coord>
+where
+    Button: Clone + PartialEq,
+    Coord: Copy + Default + Add<Output = Coord> + Into<f64>,
+{
+    /// Returns the pointer's velocity over the current frame, in pixels per
+    /// second (see `set_frame_duration`).
+    pub fn velocity(&self) -> [f64; 2] {
+        match self.motion.back() {
+            Some(&(from, time)) => self.velocity_between(from, time, self.position, self.now()),
+            None => [0.0, 0.0],
+        }
+    }
+
+    /// Returns the pointer's average velocity over the last few frames (see
+    /// `set_motion_samples`), which is less noisy than `velocity`.
+    pub fn smoothed_velocity(&self) -> [f64; 2] {
+        match self.motion.front() {
+            Some(&(from, time)) => self.velocity_between(from, time, self.position, self.now()),
+            None => [0.0, 0.0],
+        }
+    }
+
+    /// Returns the change in `velocity` since the previous frame, per
+    /// second.
+    pub fn acceleration(&self) -> [f64; 2] {
+        let (previous, frame_start) = match self.motion.len() {
+            0 | 1 => return [0.0, 0.0],
+            n => {
+                let (from, from_time) = self.motion[n - 2];
+                let (to, to_time) = self.motion[n - 1];
+                (self.velocity_between(from, from_time, to, to_time), to_time)
+            }
+        };
+        let [vx, vy] = self.velocity();
+        let dt = self.now().saturating_sub(frame_start).as_secs_f64();
+        match dt > 0.0 {
+            true => [(vx - previous[0]) / dt, (vy - previous[1]) / dt],
+            false => [0.0, 0.0],
+        }
+    }
+
+    fn now(&self) -> Duration {
+        self.history.now()
+    }
+
+    fn velocity_between(
+        &self,
+        [x0, y0]: [Coord; 2],
+        t0: Duration,
+        [x1, y1]: [Coord; 2],
+        t1: Duration,
+    ) -> [f64; 2] {
+        let (x0, y0, x1, y1) = (x0.into(), y0.into(), x1.into(), y1.into());
+        let dt = t1.saturating_sub(t0).as_secs_f64();
+        match dt > 0.0 {
+            true => [(x1 - x0) / dt, (y1 - y0) / dt],
+            false => [0.0, 0.0],
+        }
+    }
+}
+
+/// Conversion between physical pixels, which `position` is measured in, and
+/// logical points, which are physical pixels divided by the scale factor.
+impl<Button> Mouse<Button, f64>
+where
+    Button: Clone + PartialEq,
+{
+    /// Returns the ratio of physical pixels to logical points.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Set the ratio of physical pixels to logical points, such as from
+    /// the window's initial scale factor. Positions are left as they are,
+    /// since they are already in physical pixels. Factors that aren't
+    /// positive are ignored.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) -> &mut Self {
+        if scale_factor.is_finite() && scale_factor > 0.0 {
+            self.scale_factor = scale_factor;
+        }
+        self
+    }
+
+    /// Register that the ratio of physical pixels to logical points changed,
+    /// such as when the window moves to another monitor. The pointer keeps
+    /// its logical position, so its physical position is rescaled. Factors
+    /// that aren't positive are ignored.
+    pub fn scale_factor_changed(&mut self, scale_factor: f64) -> &mut Self {
+        if !(scale_factor.is_finite() && scale_factor > 0.0) {
+            return self;
+        }
+        let [x, y] = self.position;
+        let ratio = scale_factor / self.scale_factor;
+        self.position = [x * ratio, y * ratio];
+        for (position, _) in &mut self.motion {
+            let [x, y] = *position;
+            *position = [x * ratio, y * ratio];
+        }
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Returns the position of the pointer in physical pixels.
+    pub fn physical_position(&self) -> [f64; 2] {
+        self.position
+    }
+
+    /// Returns the position of the pointer in logical points.
+    pub fn logical_position(&self) -> [f64; 2] {
+        self.to_logical(self.position)
+    }
+
+    /// Convert a position in physical pixels to logical points.
+    pub fn to_logical(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [x / self.scale_factor, y / self.scale_factor]
+    }
+
+    /// Convert a position in logical points to physical pixels.
+    pub fn to_physical(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [x * self.scale_factor, y * self.scale_factor]
+    }
+}
+
+impl<B, C> MouseQuery for Mouse<B, C>
 where
     B: Clone + PartialEq,
     C: Copy + Default + Add<Output = C>,
@@ -105,6 +442,22 @@ where
         self.position
     }
 
+    fn inside_window(&self) -> bool {
+        self.inside_window
+    }
+
+    fn entered_window(&self) -> bool {
+        self.entered_window
+    }
+
+    fn left_window(&self) -> bool {
+        self.left_window
+    }
+
+    fn motion_delta(&self) -> [Self::Coord; 2] {
+        self.motion_delta
+    }
+
     fn down(&self, button: &Self::Button) -> bool {
         self.buttons_down.iter().any(|b| b == button)
     }
@@ -117,27 +470,84 @@ where
         self.buttons_released.iter().any(|b| b == button)
     }
 
+    fn buttons_down(&self) -> impl Iterator<Item = &Self::Button> {
+        self.buttons_down.iter()
+    }
+
+    fn buttons_pressed(&self) -> impl Iterator<Item = &Self::Button> {
+        self.buttons_pressed.iter()
+    }
+
+    fn buttons_released(&self) -> impl Iterator<Item = &Self::Button> {
+        self.buttons_released.iter()
+    }
+
+    fn pressed_within<W: Into<Within>>(&self, button: &Self::Button, within: W) -> bool {
+        self.history.within(button, true, within.into())
+    }
+
+    fn released_within<W: Into<Within>>(&self, button: &Self::Button, within: W) -> bool {
+        self.history.within(button, false, within.into())
+    }
+
+    fn events(&self) -> impl Iterator<Item = &MouseEvent<Self::Button, Self::Coord>> {
+        self.events.iter()
+    }
+}
+
+impl<B, C> MouseInterface for Mouse<B, C>
+where
+    B: Clone + PartialEq,
+    C: Copy + Default + Add<Output = C>,
+{
     fn clear_presses(&mut self) -> &mut Self {
         self.buttons_pressed.clear();
         self.buttons_released.clear();
+        self.entered_window = false;
+        self.left_window = false;
+        self.motion_delta = Default::default();
+        self.events.clear();
+        self.history.next_frame();
+
+        self.motion.push_back((self.position, self.history.time()));
+        if self.motion.len() > self.motion_samples {
+            self.motion.pop_front();
+        }
         self
     }
 
     fn move_to(&mut self, position: [Self::Coord; 2]) -> &mut Self {
         self.position = position;
+        self.events.record(MouseEvent::Moved(position));
         self
     }
 
     fn move_by(&mut self, [x, y]: [Self::Coord; 2]) -> &mut Self {
         let [ox, oy] = self.position;
         self.position = [ox + x, oy + y];
+        self.events.record(MouseEvent::Moved(self.position));
+        self
+    }
+
+    fn add_motion_delta(&mut self, [x, y]: [Self::Coord; 2]) -> &mut Self {
+        let [dx, dy] = self.motion_delta;
+        self.motion_delta = [dx + x, dy + y];
         self
     }
 
+    fn warp_to(&mut self, position: [Self::Coord; 2]) -> &mut Self {
+        self.motion.clear();
+        self.move_to(position)
+    }
+
     fn press(&mut self, button: Self::Button) -> &mut Self {
         if !self.down(&button) {
             self.buttons_down.push(button.clone());
         }
+        self.history.record(button.clone(), true);
+        if self.events.enabled() {
+            self.events.record(MouseEvent::Pressed(button.clone()));
+        }
         if !self.pressed(&button) {
             self.buttons_pressed.push(button);
         }
@@ -146,11 +556,38 @@ where
 
     fn release(&mut self, button: Self::Button) -> &mut Self {
         self.buttons_down.retain(|b| b != &button);
+        self.history.record(button.clone(), false);
+        if self.events.enabled() {
+            self.events.record(MouseEvent::Released(button.clone()));
+        }
         if !self.released(&button) {
             self.buttons_released.push(button);
         }
         self
     }
+
+    fn release_all(&mut self) -> &mut Self {
+        for button in std::mem::take(&mut self.buttons_down) {
+            self.release(button);
+        }
+        self
+    }
+
+    fn enter_window(&mut self) -> &mut Self {
+        // The pointer's path outside the window is unknown.
+        self.motion.clear();
+        self.inside_window = true;
+        self.entered_window = true;
+        self.events.record(MouseEvent::Entered);
+        self
+    }
+
+    fn leave_window(&mut self) -> &mut Self {
+        self.inside_window = false;
+        self.left_window = true;
+        self.events.record(MouseEvent::Left);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -177,6 +614,75 @@ mod tests {
         assert_eq!(mouse.position(), [100.0, 100.0]);
     }
 
+    #[test]
+    fn mouse_tracks_leaving_and_entering_the_window() {
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+        assert!(mouse.inside_window());
+
+        mouse.move_to([10.0, 20.0]).leave_window();
+        assert!(mouse.left_window());
+        assert_eq!(mouse.position_in_window(), None);
+        assert_eq!(mouse.position(), [10.0, 20.0]);
+
+        mouse.clear_presses();
+        assert!(!mouse.left_window());
+        mouse.enter_window();
+        assert!(mouse.entered_window());
+        assert_eq!(mouse.position_in_window(), Some([10.0, 20.0]));
+    }
+
+    #[test]
+    fn mouse_keeps_logical_position_when_scale_changes() {
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+        mouse.set_scale_factor(2.0).move_to([100.0, 50.0]);
+        assert_eq!(mouse.logical_position(), [50.0, 25.0]);
+
+        mouse.scale_factor_changed(1.5);
+        assert_eq!(mouse.logical_position(), [50.0, 25.0]);
+        assert_eq!(mouse.physical_position(), [75.0, 37.5]);
+
+        mouse.scale_factor_changed(0.0);
+        assert_eq!(mouse.scale_factor(), 1.5);
+    }
+
+    #[test]
+    fn mouse_scale_factor_set_after_moving_keeps_physical_position() {
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+        mouse.move_to([100.0, 50.0]).set_scale_factor(2.0);
+        assert_eq!(mouse.physical_position(), [100.0, 50.0]);
+        assert_eq!(mouse.logical_position(), [50.0, 25.0]);
+
+        mouse.set_scale_factor(0.0);
+        assert_eq!(mouse.scale_factor(), 2.0);
+    }
+
+    #[test]
+    fn mouse_velocity_from_recent_frames() {
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+        mouse.set_frame_duration(Duration::from_secs(1));
+        for x in [0.0, 10.0, 30.0] {
+            mouse.move_to([x, 0.0]).clear_presses();
+        }
+        mouse
+            .set_frame_duration(Duration::from_millis(500))
+            .move_to([60.0, 0.0]);
+        assert_eq!(mouse.velocity(), [60.0, 0.0]);
+        assert_eq!(mouse.acceleration(), [80.0, 0.0]);
+        assert_eq!(mouse.smoothed_velocity(), [24.0, 0.0]);
+
+        mouse.warp_to([0.0, 0.0]);
+        assert_eq!(mouse.velocity(), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn mouse_velocity_with_integer_coordinates() {
+        let mut mouse: Mouse<usize, i32> = Mouse::new();
+        mouse.set_frame_duration(Duration::from_secs(1));
+        mouse.move_to([0, 0]).clear_presses();
+        mouse.move_to([-5, 10]);
+        assert_eq!(mouse.velocity(), [-5.0, 10.0]);
+    }
+
     #[test]
     fn mouse_can_be_placed() {
         let mut mouse: Mouse<usize, f64> = Mouse::new();
@@ -250,4 +756,55 @@ mod tests {
         mouse.clear_presses();
         assert!(mouse.down(&1));
     }
+
+    #[test]
+    fn mouse_button_pressed_within_remembers_recent_frames() {
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+        mouse.press(1).release(1);
+        mouse.clear_presses();
+        assert!(mouse.pressed_within(&1, 1));
+        assert!(mouse.released_within(&1, 1));
+        mouse.clear_presses();
+        assert!(!mouse.pressed_within(&1, 1));
+        assert!(mouse.pressed_within(&1, Duration::from_millis(40)));
+    }
+
+    #[test]
+    fn mouse_release_all_releases_held_buttons() {
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+        mouse.press(1).press(2);
+        mouse.clear_presses();
+        mouse.release_all();
+        assert!(mouse.released(&1) && mouse.released(&2));
+        assert!(!mouse.down(&1) && !mouse.down(&2));
+        assert_eq!(mouse.buttons_down().count(), 0);
+    }
+
+    #[test]
+    fn mouse_event_log_keeps_order() {
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+        mouse.press(1);
+        assert_eq!(mouse.events().count(), 0);
+
+        mouse.set_event_log(true);
+        mouse
+            .enter_window()
+            .move_to([1.0, 2.0])
+            .press(1)
+            .release(1)
+            .leave_window();
+        assert_eq!(
+            mouse.events().cloned().collect::<Vec<_>>(),
+            vec![
+                MouseEvent::Entered,
+                MouseEvent::Moved([1.0, 2.0]),
+                MouseEvent::Pressed(1),
+                MouseEvent::Released(1),
+                MouseEvent::Left,
+            ]
+        );
+
+        mouse.clear_presses();
+        assert_eq!(mouse.events().count(), 0);
+    }
 }
@@ -0,0 +1,164 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// A trait for keys and buttons that can be converted to and from
+/// human-readable names, for example when storing bindings in config files.
+///
+/// Names are parsed case-insensitively, and some keys accept aliases (such
+/// as `Esc` for `Escape`), but `name` always returns the same canonical name.
+pub trait KeyName: Sized {
+    /// Returns the canonical name of this key.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Returns the key with the given name or alias, if there is one.
+    fn from_name(name: &str) -> Option<Self>;
+}
+
+impl KeyName for char {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(self.to_string())
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Some(ch),
+            _ => None,
+        }
+    }
+}
+
+/// A wrapper that implements `FromStr` and `Display` for any `KeyName`.
+///
+/// # Examples
+///
+/// ```rust
+/// use buttons::Named;
+///
+/// let Named(key): Named<char> = "x".parse().unwrap();
+/// assert_eq!(key, 'x');
+/// assert_eq!(Named('x').to_string(), "x");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Named<K>(pub K);
+
+impl<K: KeyName> FromStr for Named<K> {
+    type Err = UnknownKeyName;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        K::from_name(s.trim())
+            .map(Named)
+            .ok_or_else(|| UnknownKeyName(s.to_owned()))
+    }
+}
+
+impl<K: KeyName> fmt::Display for Named<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0.name())
+    }
+}
+
+/// An error returned when a key name is not recognized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKeyName(pub String);
+
+impl fmt::Display for UnknownKeyName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown key name `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownKeyName {}
+
+/// A runtime table of key names, for key types that don't implement
+/// `KeyName` or whose names should be customized.
+///
+/// The first name registered for a key is its canonical name; any later
+/// names are accepted as aliases when looking keys up.
+#[derive(Debug, Clone)]
+pub struct KeyNames<K> {
+    entries: Vec<(K, Cow<'static, str>)>,
+}
+
+impl<K: PartialEq> Default for KeyNames<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: PartialEq> KeyNames<K> {
+    pub fn new() -> Self {
+        KeyNames {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Register a name (or alias) for a key.
+    pub fn insert<S: Into<Cow<'static, str>>>(&mut self, key: K, name: S) -> &mut Self {
+        self.entries.push((key, name.into()));
+        self
+    }
+
+    /// Builder-style version of `insert`.
+    pub fn with<S: Into<Cow<'static, str>>>(mut self, key: K, name: S) -> Self {
+        self.insert(key, name);
+        self
+    }
+
+    /// Returns the canonical name of the given key.
+    pub fn name(&self, key: &K) -> Option<&str> {
+        name_in(self.entries.iter().map(|(k, n)| (k, n.as_ref())), key)
+    }
+
+    /// Returns the key with the given name or alias, ignoring case.
+    pub fn lookup(&self, name: &str) -> Option<&K> {
+        lookup_in(self.entries.iter().map(|(k, n)| (k, n.as_ref())), name)
+    }
+}
+
+pub(crate) fn name_in<'a, K, I>(entries: I, key: &K) -> Option<&'a str>
+where
+    K: PartialEq + 'a,
+    I: IntoIterator<Item = (&'a K, &'a str)>,
+{
+    entries
+        .into_iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, name)| name)
+}
+
+pub(crate) fn lookup_in<'a, K, I>(entries: I, name: &str) -> Option<&'a K>
+where
+    K: 'a,
+    I: IntoIterator<Item = (&'a K, &'a str)>,
+{
+    entries
+        .into_iter()
+        .find(|(_, n)| n.eq_ignore_ascii_case(name))
+        .map(|(key, _)| key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_names_use_first_name_as_canonical() {
+        let names = KeyNames::new()
+            .with(1, "Escape")
+            .with(1, "Esc")
+            .with(2, "Enter");
+        assert_eq!(names.name(&1), Some("Escape"));
+        assert_eq!(names.lookup("esc"), Some(&1));
+        assert_eq!(names.lookup("ENTER"), Some(&2));
+        assert_eq!(names.lookup("Space"), None);
+    }
+
+    #[test]
+    fn named_round_trips() {
+        let named: Named<char> = "q".parse().unwrap();
+        assert_eq!(named.to_string(), "q");
+        assert!("qq".parse::<Named<char>>().is_err());
+    }
+}
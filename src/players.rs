@@ -0,0 +1,454 @@
+use crate::Event;
+use crate::bindings::{Binding, Bindings};
+use crate::keyboard::{KeyboardQuery, ModifierState};
+use crate::mouse::MouseQuery;
+use crate::router::Router;
+use crate::shortcut::ModifierMatch;
+use crate::touch::{Touch, TouchQuery};
+
+/// A device, or part of a device, that can be assigned to a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputSource<Id> {
+    Keyboard(Id),
+    Mouse(Id),
+
+    /// An area of a shared touch screen, numbered by the application.
+    TouchRegion(usize),
+}
+
+/// A change to player slot assignments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerEvent<Id> {
+    /// A player joined using the given source.
+    Joined {
+        player: usize,
+        source: InputSource<Id>,
+    },
+
+    /// A player left, releasing all of their sources.
+    Left { player: usize },
+
+    /// One of a player's sources was disconnected. The player keeps their
+    /// slot, so the device can be reassigned when it comes back.
+    Disconnected {
+        player: usize,
+        source: InputSource<Id>,
+    },
+
+    /// A source was moved from one player to another.
+    Reassigned {
+        from: usize,
+        to: usize,
+        source: InputSource<Id>,
+    },
+}
+
+/// Assigns keyboards, mice and touch regions to a fixed number of player
+/// slots, for "press any button to join" flows in local multiplayer.
+///
+/// Changes are reported through `events` until `clear_events` is called,
+/// which should happen at the end of each frame.
+#[derive(Debug, Clone)]
+pub struct PlayerSlots<Id> {
+    slots: Vec<Option<Vec<InputSource<Id>>>>,
+    events: Vec<PlayerEvent<Id>>,
+}
+
+impl<Id> PlayerSlots<Id>
+where
+    Id: Clone + PartialEq,
+{
+    /// Create slots for up to `max_players` players.
+    pub fn new(max_players: usize) -> Self {
+        PlayerSlots {
+            slots: vec![None; max_players],
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns the number of player slots.
+    pub fn max_players(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns `true` if the given player has joined.
+    pub fn joined(&self, player: usize) -> bool {
+        matches!(self.slots.get(player), Some(Some(_)))
+    }
+
+    /// Returns an iterator over the players that have joined.
+    pub fn players(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.slots.len()).filter(|&player| self.joined(player))
+    }
+
+    /// Returns the sources assigned to the given player.
+    pub fn sources(&self, player: usize) -> &[InputSource<Id>] {
+        match self.slots.get(player) {
+            Some(Some(sources)) => sources,
+            _ => &[],
+        }
+    }
+
+    /// Returns the player the given source is assigned to, if any.
+    pub fn player_for(&self, source: &InputSource<Id>) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref().is_some_and(|s| s.contains(source)))
+    }
+
+    /// Assign the source to the first free slot, unless it already belongs
+    /// to a player. Returns the player, or `None` if every slot is taken.
+    pub fn join(&mut self, source: InputSource<Id>) -> Option<usize> {
+        if let Some(player) = self.player_for(&source) {
+            return Some(player);
+        }
+        let player = self.slots.iter().position(Option::is_none)?;
+        self.slots[player] = Some(vec![source.clone()]);
+        self.events.push(PlayerEvent::Joined { player, source });
+        Some(player)
+    }
+
+    /// Assign the source to a specific player, taking it from any other
+    /// player that had it. The player joins if they haven't already.
+    pub fn assign(&mut self, player: usize, source: InputSource<Id>) -> &mut Self {
+        if player >= self.slots.len() {
+            return self;
+        }
+        let from = self.player_for(&source);
+        if from == Some(player) {
+            return self;
+        }
+        if !self.joined(player) {
+            self.events.push(PlayerEvent::Joined {
+                player,
+                source: source.clone(),
+            });
+        }
+        if let Some(from) = from {
+            self.remove_source(from, &source);
+            self.events.push(PlayerEvent::Reassigned {
+                from,
+                to: player,
+                source: source.clone(),
+            });
+        }
+        self.slots[player].get_or_insert_with(Vec::new).push(source);
+        self
+    }
+
+    /// Remove a player, freeing their slot and sources.
+    pub fn leave(&mut self, player: usize) -> &mut Self {
+        if let Some(slot) = self.slots.get_mut(player)
+            && slot.take().is_some()
+        {
+            self.events.push(PlayerEvent::Left { player });
+        }
+        self
+    }
+
+    /// Register that a device was disconnected. Its player, if any, keeps
+    /// their slot.
+    pub fn disconnect(&mut self, source: &InputSource<Id>) -> &mut Self {
+        if let Some(player) = self.player_for(source) {
+            self.remove_source(player, source);
+            self.events.push(PlayerEvent::Disconnected {
+                player,
+                source: source.clone(),
+            });
+        }
+        self
+    }
+
+    /// Join any keyboard that had a key pressed this frame.
+    pub fn join_keyboards<K: KeyboardQuery>(&mut self, keyboards: &Router<Id, K>) -> &mut Self {
+        for (id, keyboard) in keyboards.iter() {
+            if keyboard.keys_pressed().next().is_some() {
+                self.join(InputSource::Keyboard(id.clone()));
+            }
+        }
+        self
+    }
+
+    /// Join any mouse that had a button pressed this frame.
+    pub fn join_mice<M: MouseQuery>(&mut self, mice: &Router<Id, M>) -> &mut Self {
+        for (id, mouse) in mice.iter() {
+            if mouse.buttons_pressed().next().is_some() {
+                self.join(InputSource::Mouse(id.clone()));
+            }
+        }
+        self
+    }
+
+    /// Join any touch region that was tapped this frame. `region_of` maps
+    /// a touch position to the region containing it.
+    pub fn join_touch_regions<T, F>(&mut self, touchpad: &T, region_of: F) -> &mut Self
+    where
+        T: TouchQuery,
+        F: Fn([T::Coord; 2]) -> Option<usize>,
+    {
+        for touch in touchpad.touches().filter(|t| t.tapped) {
+            if let Some(region) = region_of(touch.position) {
+                self.join(InputSource::TouchRegion(region));
+            }
+        }
+        self
+    }
+
+    /// Returns a view of the given player's keyboard, mouse and touch
+    /// regions, for checking actions with their bindings.
+    pub fn actions<'a, K, M>(
+        &self,
+        player: usize,
+        keyboards: &'a Router<Id, K>,
+        mice: &'a Router<Id, M>,
+        bindings: &'a Bindings<K::Key, M::Button>,
+    ) -> PlayerActions<'a, K, M>
+    where
+        K: KeyboardQuery,
+        M: MouseQuery,
+    {
+        let sources = self.sources(player);
+        let keyboard = sources.iter().find_map(|s| match s {
+            InputSource::Keyboard(id) => keyboards.get(id),
+            _ => None,
+        });
+        let mouse = sources.iter().find_map(|s| match s {
+            InputSource::Mouse(id) => mice.get(id),
+            _ => None,
+        });
+        let touch_regions = sources
+            .iter()
+            .filter_map(|s| match s {
+                InputSource::TouchRegion(region) => Some(*region),
+                _ => None,
+            })
+            .collect();
+        PlayerActions {
+            keyboard,
+            mouse,
+            touch_regions,
+            bindings,
+            rule: ModifierMatch::default(),
+        }
+    }
+
+    /// Convenience method for handling events, such as a device being
+    /// removed.
+    pub fn handle_event<E: Event<Self>>(&mut self, event: &E) -> &mut Self {
+        event.handle(self);
+        self
+    }
+
+    /// Returns an iterator over this frame's assignment changes.
+    pub fn events(&self) -> impl Iterator<Item = &PlayerEvent<Id>> {
+        self.events.iter()
+    }
+
+    /// Clears the list of assignment changes. Should be called at end of
+    /// frame.
+    pub fn clear_events(&mut self) -> &mut Self {
+        self.events.clear();
+        self
+    }
+
+    fn remove_source(&mut self, player: usize, source: &InputSource<Id>) {
+        if let Some(Some(sources)) = self.slots.get_mut(player) {
+            sources.retain(|s| s != source);
+        }
+    }
+}
+
+/// A view of a single player's devices, for checking actions against a set
+/// of `Bindings`.
+#[derive(Debug)]
+pub struct PlayerActions<'a, K: KeyboardQuery, M: MouseQuery> {
+    keyboard: Option<&'a K>,
+    mouse: Option<&'a M>,
+    touch_regions: Vec<usize>,
+    bindings: &'a Bindings<K::Key, M::Button>,
+    rule: ModifierMatch,
+}
+
+impl<'a, K, M> PlayerActions<'a, K, M>
+where
+    K: KeyboardQuery,
+    K::Key: PartialEq,
+    K::Mods: ModifierState,
+    M: MouseQuery,
+    M::Button: PartialEq,
+{
+    /// Set how held modifiers are matched against key chords.
+    pub fn rule(mut self, rule: ModifierMatch) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Returns the player's keyboard, if they have one.
+    pub fn keyboard(&self) -> Option<&'a K> {
+        self.keyboard
+    }
+
+    /// Returns the player's mouse, if they have one.
+    pub fn mouse(&self) -> Option<&'a M> {
+        self.mouse
+    }
+
+    /// Returns the touch regions assigned to the player.
+    pub fn touch_regions(&self) -> &[usize] {
+        &self.touch_regions
+    }
+
+    /// Returns an iterator over the touches in the player's regions.
+    /// `region_of` maps a touch position to the region containing it, as in
+    /// `PlayerSlots::join_touch_regions`.
+    pub fn touches<'t, T, F>(
+        &self,
+        touchpad: &'t T,
+        region_of: F,
+    ) -> impl Iterator<Item = &'t Touch<T::TouchId, T::Coord>>
+    where
+        T: TouchQuery,
+        F: Fn([T::Coord; 2]) -> Option<usize>,
+    {
+        let regions = self.touch_regions.clone();
+        touchpad
+            .touches()
+            .filter(move |t| region_of(t.position).is_some_and(|r| regions.contains(&r)))
+    }
+
+    /// Returns `true` if any binding for the action was pressed this frame
+    /// on the player's devices.
+    pub fn pressed(&self, action: &str) -> bool {
+        self.bindings.bindings(action).iter().any(|b| match b {
+            Binding::Key(shortcut) => self
+                .keyboard
+                .is_some_and(|k| shortcut.pressed(k, self.rule)),
+            Binding::Button(button) => self.mouse.is_some_and(|m| m.pressed(button)),
+        })
+    }
+
+    /// Returns `true` if any binding for the action is held down on the
+    /// player's devices.
+    pub fn down(&self, action: &str) -> bool {
+        self.bindings.bindings(action).iter().any(|b| match b {
+            Binding::Key(shortcut) => self.keyboard.is_some_and(|k| shortcut.down(k, self.rule)),
+            Binding::Button(button) => self.mouse.is_some_and(|m| m.down(button)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::touch::TouchPhase;
+    use crate::{
+        Keyboard, KeyboardInterface, Modifiers, Mouse, Shortcut, TouchInterface, Touchpad,
+    };
+
+    type TestKeyboard = Keyboard<char, Modifiers>;
+    type TestMouse = Mouse<u8, f64>;
+
+    struct Press(char);
+
+    impl Event<TestKeyboard> for Press {
+        fn handle(&self, keyboard: &mut TestKeyboard) {
+            keyboard.press(self.0);
+        }
+    }
+
+    #[test]
+    fn keyboards_join_and_see_only_their_own_actions() {
+        let mut keyboards: Router<u32, TestKeyboard> = Router::new();
+        let mice: Router<u32, TestMouse> = Router::new();
+        let bindings = Bindings::new().with("jump", Binding::Key(Shortcut::key(' ')));
+
+        let mut slots = PlayerSlots::new(2);
+        keyboards.dispatch(7, &Press('x')).dispatch(9, &Press(' '));
+        slots.join_keyboards(&keyboards);
+        assert_eq!(slots.players().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(slots.player_for(&InputSource::Keyboard(9)), Some(1));
+
+        assert!(
+            !slots
+                .actions(0, &keyboards, &mice, &bindings)
+                .pressed("jump")
+        );
+        assert!(
+            slots
+                .actions(1, &keyboards, &mice, &bindings)
+                .pressed("jump")
+        );
+    }
+
+    #[test]
+    fn track_disconnects_and_reassignments() {
+        let mut slots: PlayerSlots<u32> = PlayerSlots::new(2);
+        slots.join(InputSource::Keyboard(1));
+        slots.join(InputSource::Mouse(2));
+        slots.clear_events();
+
+        slots.disconnect(&InputSource::Keyboard(1));
+        slots.assign(0, InputSource::Mouse(2));
+        slots.leave(1);
+        assert!(slots.joined(0));
+        assert_eq!(slots.sources(0), &[InputSource::Mouse(2)]);
+        assert_eq!(
+            slots.events().cloned().collect::<Vec<_>>(),
+            vec![
+                PlayerEvent::Disconnected {
+                    player: 0,
+                    source: InputSource::Keyboard(1)
+                },
+                PlayerEvent::Reassigned {
+                    from: 1,
+                    to: 0,
+                    source: InputSource::Mouse(2)
+                },
+                PlayerEvent::Left { player: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn assigning_a_taken_source_joins_the_new_player() {
+        let mut slots: PlayerSlots<u32> = PlayerSlots::new(2);
+        slots.join(InputSource::Keyboard(1));
+        slots.clear_events();
+
+        slots.assign(1, InputSource::Keyboard(1));
+        assert!(slots.joined(1));
+        assert_eq!(
+            slots.events().cloned().collect::<Vec<_>>(),
+            vec![
+                PlayerEvent::Joined {
+                    player: 1,
+                    source: InputSource::Keyboard(1)
+                },
+                PlayerEvent::Reassigned {
+                    from: 0,
+                    to: 1,
+                    source: InputSource::Keyboard(1)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn players_see_touches_in_their_regions() {
+        let keyboards: Router<u32, TestKeyboard> = Router::new();
+        let mice: Router<u32, TestMouse> = Router::new();
+        let bindings = Bindings::new();
+        let mut touchpad: Touchpad<u64, f64> = Touchpad::new();
+        touchpad
+            .touch_event(1u64, [10.0, 0.0], TouchPhase::Start)
+            .touch_event(2u64, [90.0, 0.0], TouchPhase::Start);
+        let half = |[x, _]: [f64; 2]| Some(if x < 50.0 { 0 } else { 1 });
+
+        let mut slots = PlayerSlots::new(2);
+        slots.join_touch_regions(&touchpad, half);
+        let right = slots.player_for(&InputSource::TouchRegion(1)).unwrap();
+        let actions = slots.actions(right, &keyboards, &mice, &bindings);
+        let ids: Vec<u64> = actions.touches(&touchpad, half).map(|t| t.id).collect();
+        assert_eq!(ids, vec![2]);
+    }
+}
@@ -0,0 +1,254 @@
+use std::marker::PhantomData;
+
+use crate::mouse::MouseQuery;
+use crate::touch::TouchQuery;
+
+/// Identifies a pointer: the mouse, or one touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerId<TouchId> {
+    Mouse,
+    Touch(TouchId),
+}
+
+/// The kind of device behind a pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerKind {
+    Mouse,
+    Touch,
+
+    /// A pen or stylus. The adapters can't tell pens apart from the mice or
+    /// touches they emulate, so this is only reported when requested with
+    /// `with_kind`.
+    Pen,
+}
+
+/// The state of a single pointer this frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pointer<Id, Coord> {
+    pub id: Id,
+    pub kind: PointerKind,
+    pub position: [Coord; 2],
+
+    /// `true` if the primary button (or the touch itself) is held down.
+    pub down: bool,
+
+    /// `true` if the primary button was pressed, or the touch started, this
+    /// frame.
+    pub pressed: bool,
+
+    /// `true` if the primary button was released, or the touch ended, this
+    /// frame.
+    pub released: bool,
+}
+
+/// A trait for objects that can represent one or more pointers, so that UI
+/// code can treat mouse, touch and pen input alike.
+///
+/// A pair of pointer sources with the same id and coordinate types is also a
+/// pointer source, so a mouse and a touchpad can be combined with
+/// `(mouse_pointer, touch_pointers)`.
+pub trait PointerInterface {
+    /// A type that uniquely identifies a pointer.
+    type PointerId: PartialEq;
+
+    /// The numeric type used for pointer coordinates.
+    type Coord: Copy;
+
+    /// Returns an iterator over every active pointer. The first is the
+    /// primary pointer.
+    fn pointers(&self) -> impl Iterator<Item = Pointer<Self::PointerId, Self::Coord>>;
+
+    /// Returns the pointer with the given id, if it is active.
+    fn pointer(&self, id: &Self::PointerId) -> Option<Pointer<Self::PointerId, Self::Coord>> {
+        self.pointers().find(|p| &p.id == id)
+    }
+
+    /// Returns the primary pointer, if any.
+    fn primary(&self) -> Option<Pointer<Self::PointerId, Self::Coord>> {
+        self.pointers().next()
+    }
+
+    /// Returns the position of the given pointer, if it is active.
+    fn position(&self, id: &Self::PointerId) -> Option<[Self::Coord; 2]> {
+        self.pointer(id).map(|p| p.position)
+    }
+
+    /// Returns `true` if the given pointer is held down.
+    fn down(&self, id: &Self::PointerId) -> bool {
+        self.pointer(id).is_some_and(|p| p.down)
+    }
+
+    /// Returns `true` if the given pointer was pressed this frame.
+    fn pressed(&self, id: &Self::PointerId) -> bool {
+        self.pointer(id).is_some_and(|p| p.pressed)
+    }
+
+    /// Returns `true` if the given pointer was released this frame.
+    fn released(&self, id: &Self::PointerId) -> bool {
+        self.pointer(id).is_some_and(|p| p.released)
+    }
+}
+
+impl<A, B> PointerInterface for (A, B)
+where
+    A: PointerInterface,
+    B: PointerInterface<PointerId = A::PointerId, Coord = A::Coord>,
+{
+    type PointerId = A::PointerId;
+    type Coord = A::Coord;
+
+    fn pointers(&self) -> impl Iterator<Item = Pointer<Self::PointerId, Self::Coord>> {
+        self.0.pointers().chain(self.1.pointers())
+    }
+}
+
+/// Presents a mouse as a single pointer, using one of its buttons as the
+/// primary button.
+///
+/// The pointer is left out while the mouse is outside the window, unless
+/// the primary button is held or was just released, so drags that leave the
+/// window still end.
+///
+/// `TouchId` is only used in the pointer id, so that the mouse can be
+/// combined with `TouchPointers`.
+#[derive(Debug)]
+pub struct MousePointer<'a, M: MouseQuery, TouchId = u64> {
+    mouse: &'a M,
+    button: M::Button,
+    kind: PointerKind,
+    _touch_id: PhantomData<TouchId>,
+}
+
+impl<'a, M, TouchId> MousePointer<'a, M, TouchId>
+where
+    M: MouseQuery,
+{
+    pub fn new(mouse: &'a M, button: M::Button) -> Self {
+        MousePointer {
+            mouse,
+            button,
+            kind: PointerKind::Mouse,
+            _touch_id: PhantomData,
+        }
+    }
+
+    /// Report a different kind of device, for example a pen tablet that
+    /// emulates a mouse.
+    pub fn with_kind(mut self, kind: PointerKind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
+impl<M, TouchId> PointerInterface for MousePointer<'_, M, TouchId>
+where
+    M: MouseQuery,
+    M::Coord: Copy,
+    TouchId: PartialEq,
+{
+    type PointerId = PointerId<TouchId>;
+    type Coord = M::Coord;
+
+    fn pointers(&self) -> impl Iterator<Item = Pointer<Self::PointerId, Self::Coord>> {
+        let down = self.mouse.down(&self.button);
+        let released = self.mouse.released(&self.button);
+        let position = match self.mouse.position_in_window() {
+            None if down || released => Some(self.mouse.position()),
+            position => position,
+        };
+        position
+            .map(|position| Pointer {
+                id: PointerId::Mouse,
+                kind: self.kind,
+                position,
+                down,
+                pressed: self.mouse.pressed(&self.button),
+                released,
+            })
+            .into_iter()
+    }
+}
+
+/// Presents each active touch as a pointer.
+#[derive(Debug)]
+pub struct TouchPointers<'a, T> {
+    touchpad: &'a T,
+    kind: PointerKind,
+}
+
+impl<'a, T> TouchPointers<'a, T>
+where
+    T: TouchQuery,
+{
+    pub fn new(touchpad: &'a T) -> Self {
+        TouchPointers {
+            touchpad,
+            kind: PointerKind::Touch,
+        }
+    }
+
+    /// Report a different kind of device, for example a pen that sends
+    /// touch events.
+    pub fn with_kind(mut self, kind: PointerKind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
+impl<T> PointerInterface for TouchPointers<'_, T>
+where
+    T: TouchQuery,
+    T::TouchId: Clone,
+{
+    type PointerId = PointerId<T::TouchId>;
+    type Coord = T::Coord;
+
+    fn pointers(&self) -> impl Iterator<Item = Pointer<Self::PointerId, Self::Coord>> {
+        self.touchpad.touches().map(|touch| Pointer {
+            id: PointerId::Touch(touch.id.clone()),
+            kind: self.kind,
+            position: touch.position,
+            down: !touch.released,
+            pressed: touch.tapped,
+            released: touch.released,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::touch::TouchPhase;
+    use crate::{Mouse, MouseInterface, TouchInterface, Touchpad};
+
+    #[test]
+    fn mouse_and_touch_combine() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        let mut touchpad: Touchpad<u64, f64> = Touchpad::new();
+        mouse.move_to([1.0, 2.0]).press(0);
+        touchpad.touch_event(5u64, [3.0, 4.0], TouchPhase::Start);
+
+        let pointers = (MousePointer::new(&mouse, 0), TouchPointers::new(&touchpad));
+        assert_eq!(pointers.pointers().count(), 2);
+        assert_eq!(pointers.primary().map(|p| p.kind), Some(PointerKind::Mouse));
+        assert!(pointers.pressed(&PointerId::Mouse));
+        assert_eq!(pointers.position(&PointerId::Touch(5)), Some([3.0, 4.0]));
+        assert!(!pointers.released(&PointerId::Touch(6)));
+    }
+
+    #[test]
+    fn mouse_pointer_follows_button_and_window() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        mouse.press(0);
+        mouse.clear_presses();
+        let pointer = MousePointer::<_>::new(&mouse, 0).primary().unwrap();
+        assert!(pointer.down && !pointer.pressed && !pointer.released);
+
+        mouse.leave_window().release(0);
+        let pointer = MousePointer::<_>::new(&mouse, 0).primary().unwrap();
+        assert!(!pointer.down && pointer.released);
+
+        mouse.clear_presses();
+        assert!(MousePointer::<_>::new(&mouse, 0).primary().is_none());
+    }
+}
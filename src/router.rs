@@ -0,0 +1,221 @@
+use crate::Event;
+
+/// Routes events to separate device states by some id (such as a window or
+/// a physical device), while also keeping a merged state that receives
+/// every event.
+///
+/// The merged state behaves exactly like a single device that ignores ids,
+/// so it can be used for global shortcuts while per-id states are used for
+/// split input.
+///
+/// One id can be focused, so that events without an id which only concern
+/// one device (such as typed text) reach a single per-id state.
+#[derive(Debug, Clone)]
+pub struct Router<Id, D> {
+    merged: D,
+    routes: Vec<(Id, D)>,
+    focused: Option<Id>,
+}
+
+impl<Id, D> Default for Router<Id, D>
+where
+    Id: PartialEq,
+    D: Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id, D> Router<Id, D>
+where
+    Id: PartialEq,
+{
+    /// Returns the state that receives events for every id.
+    pub fn merged(&self) -> &D {
+        &self.merged
+    }
+
+    /// Returns the merged state mutably, for example to release inputs that
+    /// were only held by a removed id.
+    pub fn merged_mut(&mut self) -> &mut D {
+        &mut self.merged
+    }
+
+    /// Returns the state for the given id, if any events have been routed
+    /// to it.
+    pub fn get(&self, id: &Id) -> Option<&D> {
+        self.routes.iter().find(|(i, _)| i == id).map(|(_, d)| d)
+    }
+
+    /// Returns an iterator over every id and its state.
+    pub fn iter(&self) -> impl Iterator<Item = (&Id, &D)> {
+        self.routes.iter().map(|(i, d)| (i, d))
+    }
+
+    /// Returns the focused id, if any.
+    pub fn focused(&self) -> Option<&Id> {
+        self.focused.as_ref()
+    }
+
+    /// Focus the given id, so it receives events from `dispatch_focused`.
+    pub fn set_focus(&mut self, id: Id) -> &mut Self {
+        self.focused = Some(id);
+        self
+    }
+
+    /// Handle an event that isn't tied to any id, updating only the merged
+    /// state.
+    pub fn dispatch_merged<E>(&mut self, event: &E) -> &mut Self
+    where
+        E: Event<D>,
+    {
+        event.handle(&mut self.merged);
+        self
+    }
+}
+
+impl<Id, D> Router<Id, D>
+where
+    Id: PartialEq,
+    D: Default,
+{
+    pub fn new() -> Self {
+        Router {
+            merged: D::default(),
+            routes: Vec::new(),
+            focused: None,
+        }
+    }
+
+    /// Returns the state for the given id, creating it if needed.
+    pub fn route(&mut self, id: Id) -> &mut D {
+        let index = match self.routes.iter().position(|(i, _)| i == &id) {
+            Some(index) => index,
+            None => {
+                self.routes.push((id, D::default()));
+                self.routes.len() - 1
+            }
+        };
+        &mut self.routes[index].1
+    }
+
+    /// Handle an event for the given id, updating both its state and the
+    /// merged state.
+    pub fn dispatch<E>(&mut self, id: Id, event: &E) -> &mut Self
+    where
+        E: Event<D>,
+    {
+        event.handle(&mut self.merged);
+        event.handle(self.route(id));
+        self
+    }
+
+    /// Handle an event that isn't tied to any id, but only concerns the
+    /// focused one (such as a modifier change), updating the merged state
+    /// and the focused id's state. If nothing is focused, only the merged
+    /// state is updated.
+    pub fn dispatch_focused<E>(&mut self, event: &E) -> &mut Self
+    where
+        E: Event<D>,
+    {
+        event.handle(&mut self.merged);
+        if let Some(focused) = &self.focused
+            && let Some((_, state)) = self.routes.iter_mut().find(|(i, _)| i == focused)
+        {
+            event.handle(state);
+        }
+        self
+    }
+
+    /// Handle an event that isn't tied to any id (such as a focus change),
+    /// updating the merged state and every per-id state.
+    pub fn broadcast<E>(&mut self, event: &E) -> &mut Self
+    where
+        E: Event<D>,
+    {
+        self.for_each_mut(|state| event.handle(state))
+    }
+
+    /// Forget the state for the given id, for example when a window is
+    /// closed. Returns the removed state.
+    pub fn remove(&mut self, id: &Id) -> Option<D> {
+        if self.focused.as_ref() == Some(id) {
+            self.focused = None;
+        }
+        let index = self.routes.iter().position(|(i, _)| i == id)?;
+        Some(self.routes.remove(index).1)
+    }
+
+    /// Apply a function to the merged state and every per-id state, for
+    /// example to clear presses at the end of a frame.
+    pub fn for_each_mut<F: FnMut(&mut D)>(&mut self, mut f: F) -> &mut Self {
+        f(&mut self.merged);
+        for (_, state) in &mut self.routes {
+            f(state);
+        }
+        self
+    }
+
+    /// Convenience method for handling events that carry their own id.
+    pub fn handle_event<E: Event<Self>>(&mut self, event: &E) -> &mut Self {
+        event.handle(self);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keyboard, KeyboardInterface, KeyboardQuery, Modifiers};
+
+    type TestKeyboard = Keyboard<char, Modifiers>;
+
+    struct Press(char);
+
+    impl Event<TestKeyboard> for Press {
+        fn handle(&self, keyboard: &mut TestKeyboard) {
+            keyboard.press(self.0);
+        }
+    }
+
+    #[test]
+    fn routes_events_by_id_and_merges_them() {
+        let mut router: Router<u32, TestKeyboard> = Router::new();
+        router.dispatch(1, &Press('a')).dispatch(2, &Press('b'));
+        router.broadcast(&Press('c'));
+        assert!(router.get(&2).unwrap().down(&'c'));
+
+        assert!(router.merged().down(&'a') && router.merged().down(&'b'));
+        assert!(router.get(&1).unwrap().down(&'a'));
+        assert!(!router.get(&1).unwrap().down(&'b'));
+        assert!(router.get(&3).is_none());
+
+        router.for_each_mut(|k| {
+            k.clear_presses();
+        });
+        assert!(!router.merged().pressed(&'a'));
+        assert!(router.remove(&2).is_some());
+        assert_eq!(router.iter().count(), 1);
+    }
+
+    #[test]
+    fn unrouted_events_reach_only_the_focused_id() {
+        let mut router: Router<u32, TestKeyboard> = Router::new();
+        router.dispatch(1, &Press('a')).dispatch(2, &Press('b'));
+        router.dispatch_focused(&Press('c'));
+        assert!(router.merged().down(&'c'));
+        assert!(router.iter().all(|(_, k)| !k.down(&'c')));
+
+        router.set_focus(2).dispatch_focused(&Press('d'));
+        assert!(router.get(&2).unwrap().down(&'d'));
+        assert!(!router.get(&1).unwrap().down(&'d'));
+
+        router.dispatch_merged(&Press('e'));
+        assert!(router.merged().down(&'e'));
+        assert!(router.iter().all(|(_, k)| !k.down(&'e')));
+
+        router.remove(&2);
+        assert_eq!(router.focused(), None);
+    }
+}
@@ -0,0 +1,339 @@
+use crate::keyboard::KeyboardQuery;
+use crate::mouse::MouseQuery;
+
+/// A single step in a `Sequence`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<T> {
+    /// The input that must be pressed to complete this step.
+    pub input: T,
+
+    /// The maximum number of frames allowed since the previous step, if any.
+    /// This is ignored for the first step.
+    pub timeout: Option<u32>,
+}
+
+/// An ordered list of inputs that must be pressed one after another, such
+/// as a cheat code, a fighting-game motion or a multi-key command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence<T> {
+    steps: Vec<Step<T>>,
+}
+
+impl<T> Default for Sequence<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Sequence<T> {
+    pub fn new() -> Self {
+        Sequence { steps: Vec::new() }
+    }
+
+    /// Create a sequence from a list of inputs, where each step must follow
+    /// the previous one within `timeout` frames (if given).
+    pub fn from_inputs<I: IntoIterator<Item = T>>(inputs: I, timeout: Option<u32>) -> Self {
+        Sequence {
+            steps: inputs
+                .into_iter()
+                .map(|input| Step { input, timeout })
+                .collect(),
+        }
+    }
+
+    /// Append a step with no timeout.
+    pub fn then(mut self, input: T) -> Self {
+        self.steps.push(Step {
+            input,
+            timeout: None,
+        });
+        self
+    }
+
+    /// Append a step that must follow the previous one within `frames` frames.
+    pub fn then_within(mut self, input: T, frames: u32) -> Self {
+        self.steps.push(Step {
+            input,
+            timeout: Some(frames),
+        });
+        self
+    }
+
+    /// Returns the steps of the sequence.
+    pub fn steps(&self) -> &[Step<T>] {
+        &self.steps
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry<Id, T> {
+    id: Id,
+    sequence: Sequence<T>,
+    progress: usize,
+    last_step_frame: u64,
+}
+
+/// Recognizes registered `Sequence`s as their inputs are pressed.
+///
+/// Each frame, call `update_keyboard`, `update_mouse` or `update` once for
+/// each observed device, after handling events but before clearing presses.
+/// Then call `next_frame` once, so timeouts count frames rather than calls.
+#[derive(Debug, Clone)]
+pub struct SequenceMatcher<Id, T> {
+    entries: Vec<Entry<Id, T>>,
+    completed: Vec<Id>,
+    frame: u64,
+}
+
+impl<Id, T> Default for SequenceMatcher<Id, T>
+where
+    Id: Clone + PartialEq,
+    T: PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id, T> SequenceMatcher<Id, T>
+where
+    Id: Clone + PartialEq,
+    T: PartialEq,
+{
+    pub fn new() -> Self {
+        SequenceMatcher {
+            entries: Vec::new(),
+            completed: Vec::new(),
+            frame: 0,
+        }
+    }
+
+    /// Register a sequence under the given `id`, replacing any existing
+    /// sequence with the same `id`.
+    pub fn register(&mut self, id: Id, sequence: Sequence<T>) -> &mut Self {
+        self.unregister(&id);
+        self.entries.push(Entry {
+            id,
+            sequence,
+            progress: 0,
+            last_step_frame: 0,
+        });
+        self
+    }
+
+    /// Remove the sequence with the given `id`, if it exists.
+    pub fn unregister(&mut self, id: &Id) -> &mut Self {
+        self.entries.retain(|e| &e.id != id);
+        self
+    }
+
+    /// Discard all partial progress towards registered sequences.
+    pub fn reset(&mut self) -> &mut Self {
+        for entry in &mut self.entries {
+            entry.progress = 0;
+        }
+        self.completed.clear();
+        self
+    }
+
+    /// Returns `true` if the sequence with the given `id` completed this frame.
+    pub fn completed(&self, id: &Id) -> bool {
+        self.completed.iter().any(|c| c == id)
+    }
+
+    /// Returns the ids of all sequences completed this frame.
+    pub fn completions(&self) -> impl Iterator<Item = &Id> {
+        self.completed.iter()
+    }
+
+    /// Returns the number of steps completed so far for the given sequence.
+    pub fn progress(&self, id: &Id) -> Option<usize> {
+        self.entries
+            .iter()
+            .find(|e| &e.id == id)
+            .map(|e| e.progress)
+    }
+
+    /// Start a new frame, clearing this frame's completions. Call this once
+    /// per frame, after all devices have been observed.
+    pub fn next_frame(&mut self) -> &mut Self {
+        self.frame += 1;
+        self.completed.clear();
+        self
+    }
+
+    /// Observe this frame's presses. `pressed` should return `true` for each
+    /// input that was pressed this frame.
+    ///
+    /// Pressing an input that appears in any registered sequence, other than
+    /// the next expected one, breaks a sequence's progress.
+    pub fn update<F>(&mut self, pressed: F) -> &mut Self
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut pressed_input = None;
+        'search: for entry in &self.entries {
+            for step in &entry.sequence.steps {
+                if pressed(&step.input) {
+                    pressed_input = Some(&step.input);
+                    break 'search;
+                }
+            }
+        }
+
+        let frame = self.frame;
+        let updates: Vec<(usize, u64)> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let steps = &entry.sequence.steps;
+                let mut progress = entry.progress;
+                if steps.is_empty() {
+                    return (progress, entry.last_step_frame);
+                }
+
+                if progress > 0
+                    && let Some(timeout) = steps[progress].timeout
+                    && frame - entry.last_step_frame > timeout as u64
+                {
+                    progress = 0;
+                }
+
+                if pressed(&steps[progress].input) {
+                    (progress + 1, frame)
+                } else if let Some(input) = pressed_input {
+                    (fallback(steps, progress, input), frame)
+                } else {
+                    (progress, entry.last_step_frame)
+                }
+            })
+            .collect();
+
+        for (entry, (progress, last_step_frame)) in self.entries.iter_mut().zip(updates) {
+            entry.last_step_frame = last_step_frame;
+            entry.progress = progress;
+            if !entry.sequence.steps.is_empty() && progress == entry.sequence.steps.len() {
+                self.completed.push(entry.id.clone());
+                entry.progress = 0;
+            }
+        }
+
+        self
+    }
+
+    /// Observe this frame's key presses.
+    pub fn update_keyboard<K>(&mut self, keyboard: &K) -> &mut Self
+    where
+        K: KeyboardQuery<Key = T>,
+    {
+        self.update(|key| keyboard.pressed(key))
+    }
+
+    /// Observe this frame's mouse button presses.
+    pub fn update_mouse<M>(&mut self, mouse: &M) -> &mut Self
+    where
+        M: MouseQuery<Button = T>,
+    {
+        self.update(|button| mouse.pressed(button))
+    }
+}
+
+/// Returns the length of the longest prefix of `steps` that is also a suffix
+/// of the first `progress` steps followed by `input`.
+fn fallback<T: PartialEq>(steps: &[Step<T>], progress: usize, input: &T) -> usize {
+    (1..=progress)
+        .rev()
+        .find(|&len| {
+            let start = progress + 1 - len;
+            steps[len - 1].input == *input
+                && steps[..len - 1]
+                    .iter()
+                    .zip(&steps[start..progress])
+                    .all(|(a, b)| a.input == b.input)
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keyboard, KeyboardInterface, Modifiers, Mouse, MouseInterface};
+
+    const UP: usize = 0;
+    const DOWN: usize = 1;
+    const OTHER: usize = 2;
+
+    fn konami() -> SequenceMatcher<&'static str, usize> {
+        let mut matcher = SequenceMatcher::new();
+        matcher.register(
+            "code",
+            Sequence::from_inputs([UP, UP, DOWN, DOWN], Some(10)),
+        );
+        matcher.register("other", Sequence::new().then(OTHER));
+        matcher
+    }
+
+    fn press(matcher: &mut SequenceMatcher<&'static str, usize>, key: usize) {
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        keyboard.press(key);
+        matcher.next_frame().update_keyboard(&keyboard);
+    }
+
+    #[test]
+    fn sequence_completes_when_pressed_in_order() {
+        let mut matcher = konami();
+        for key in [UP, UP, DOWN] {
+            press(&mut matcher, key);
+            assert!(!matcher.completed(&"code"));
+        }
+        press(&mut matcher, DOWN);
+        assert!(matcher.completed(&"code"));
+        assert_eq!(matcher.progress(&"code"), Some(0));
+    }
+
+    #[test]
+    fn sequence_resets_on_wrong_input() {
+        let mut matcher = konami();
+        press(&mut matcher, UP);
+        press(&mut matcher, OTHER);
+        assert_eq!(matcher.progress(&"code"), Some(0));
+        assert!(matcher.completed(&"other"));
+    }
+
+    #[test]
+    fn sequence_falls_back_to_matching_suffix() {
+        let mut matcher = konami();
+        for key in [UP, UP, UP, DOWN, DOWN] {
+            press(&mut matcher, key);
+        }
+        assert!(matcher.completed(&"code"));
+    }
+
+    #[test]
+    fn sequence_resets_after_timeout() {
+        let mut matcher = konami();
+        press(&mut matcher, UP);
+        for _ in 0..10 {
+            matcher.next_frame();
+        }
+        press(&mut matcher, UP);
+        assert_eq!(matcher.progress(&"code"), Some(1));
+    }
+
+    #[test]
+    fn observing_several_devices_counts_one_frame() {
+        let mut matcher = SequenceMatcher::new();
+        matcher.register("dash", Sequence::from_inputs([UP, DOWN], Some(1)));
+        let mut keyboard: Keyboard<usize, Modifiers> = Keyboard::new();
+        let mut mouse: Mouse<usize, f64> = Mouse::new();
+
+        keyboard.press(UP);
+        matcher.update_keyboard(&keyboard).update_mouse(&mouse);
+        matcher.next_frame();
+        keyboard.clear_presses();
+
+        mouse.press(DOWN);
+        matcher.update_keyboard(&keyboard).update_mouse(&mouse);
+        assert!(matcher.completed(&"dash"));
+    }
+}
@@ -0,0 +1,317 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::keyboard::{KeyboardQuery, ModifierState, Modifiers};
+use crate::names::KeyName;
+
+/// How the held modifiers are compared against a `Shortcut`'s modifiers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierMatch {
+    /// The held modifiers must be exactly the shortcut's modifiers, so
+    /// Ctrl+Shift+S does not trigger Ctrl+S.
+    #[default]
+    Exact,
+
+    /// The shortcut's modifiers must be held, but others may be held too.
+    Subset,
+}
+
+impl ModifierMatch {
+    /// Returns `true` if `held` satisfies `required` under this rule.
+    pub fn matches(self, required: &Modifiers, held: &Modifiers) -> bool {
+        let pairs = [
+            (required.ctrl, held.ctrl),
+            (required.alt, held.alt),
+            (required.shift, held.shift),
+            (required.logo, held.logo),
+        ];
+        match self {
+            ModifierMatch::Exact => pairs.iter().all(|(r, h)| r == h),
+            ModifierMatch::Subset => pairs.iter().all(|(r, h)| !r || *h),
+        }
+    }
+}
+
+/// The convention used when displaying a `Shortcut`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// Symbols with no separator, for example `⌃⇧S`.
+    MacOs,
+
+    /// Names joined by `+`, with the logo key shown as `Win`.
+    Windows,
+
+    /// Names joined by `+`, with the logo key shown as `Super`.
+    Linux,
+}
+
+impl Platform {
+    /// Returns the platform the crate was compiled for.
+    pub fn current() -> Self {
+        if cfg!(any(target_os = "macos", target_os = "ios")) {
+            Platform::MacOs
+        } else if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
+/// A key combined with a set of modifiers, such as Ctrl+Shift+S.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut<Key> {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+impl<Key> Shortcut<Key> {
+    pub fn new(modifiers: Modifiers, key: Key) -> Self {
+        Shortcut { modifiers, key }
+    }
+
+    /// Create a shortcut with no modifiers.
+    pub fn key(key: Key) -> Self {
+        Shortcut {
+            modifiers: Modifiers::default(),
+            key,
+        }
+    }
+
+    /// Returns `true` if the shortcut's key was pressed this frame while the
+    /// modifiers were held.
+    pub fn pressed<K>(&self, keyboard: &K, rule: ModifierMatch) -> bool
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+    {
+        keyboard.pressed(&self.key) && self.modifiers_held(keyboard, rule)
+    }
+
+    /// Returns `true` if the shortcut's key is held down along with the
+    /// modifiers.
+    pub fn down<K>(&self, keyboard: &K, rule: ModifierMatch) -> bool
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+    {
+        keyboard.down(&self.key) && self.modifiers_held(keyboard, rule)
+    }
+
+    fn modifiers_held<K>(&self, keyboard: &K, rule: ModifierMatch) -> bool
+    where
+        K: KeyboardQuery<Key = Key>,
+        K::Mods: ModifierState,
+    {
+        let held = keyboard
+            .modifiers()
+            .map(ModifierState::to_modifiers)
+            .unwrap_or_default();
+        rule.matches(&self.modifiers, &held)
+    }
+
+    /// Returns an object that displays the shortcut using the conventions
+    /// of the given platform.
+    pub fn display_for(&self, platform: Platform) -> ShortcutDisplay<'_, Key> {
+        ShortcutDisplay {
+            shortcut: self,
+            platform,
+        }
+    }
+
+    /// Parse a shortcut such as `"Ctrl+Shift+S"`, using `parse_key` to parse
+    /// the final key name.
+    ///
+    /// Modifier names are case-insensitive. `Ctrl`/`Control`, `Alt`/`Opt`/
+    /// `Option`, `Shift` and `Logo`/`Super`/`Win`/`Cmd`/`Command`/`Meta` are
+    /// recognized.
+    pub fn parse_with<F>(s: &str, parse_key: F) -> Result<Self, ParseShortcutError>
+    where
+        F: FnOnce(&str) -> Option<Key>,
+    {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseShortcutError::Empty);
+        }
+
+        // A trailing `+` with nothing after it is the plus key itself.
+        let (mods, key) = match s.strip_suffix("++") {
+            Some(mods) => (Some(mods), "+"),
+            None if s == "+" => (None, "+"),
+            None => match s.rsplit_once('+') {
+                Some((mods, key)) => (Some(mods), key.trim()),
+                None => (None, s),
+            },
+        };
+
+        let mut modifiers = Modifiers::default();
+        for name in mods.into_iter().flat_map(|m| m.split('+')) {
+            let name = name.trim();
+            let flag = match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut modifiers.ctrl,
+                "alt" | "opt" | "option" => &mut modifiers.alt,
+                "shift" => &mut modifiers.shift,
+                "logo" | "super" | "win" | "cmd" | "command" | "meta" => &mut modifiers.logo,
+                _ => return Err(ParseShortcutError::UnknownModifier(name.to_owned())),
+            };
+            *flag = true;
+        }
+
+        if key.is_empty() {
+            return Err(ParseShortcutError::MissingKey);
+        }
+        let key = parse_key(key).ok_or_else(|| ParseShortcutError::UnknownKey(key.to_owned()))?;
+        Ok(Shortcut { modifiers, key })
+    }
+}
+
+impl<Key: KeyName> FromStr for Shortcut<Key> {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, Key::from_name)
+    }
+}
+
+impl<Key: KeyName> fmt::Display for Shortcut<Key> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_for(Platform::current()).fmt(f)
+    }
+}
+
+/// Displays a `Shortcut` using a particular platform's conventions.
+///
+/// Created by `Shortcut::display_for`.
+#[derive(Debug, Clone, Copy)]
+pub struct ShortcutDisplay<'a, Key> {
+    shortcut: &'a Shortcut<Key>,
+    platform: Platform,
+}
+
+impl<Key: KeyName> fmt::Display for ShortcutDisplay<'_, Key> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Modifiers {
+            ctrl,
+            alt,
+            shift,
+            logo,
+        } = self.shortcut.modifiers;
+
+        let names: [(bool, &str); 4] = match self.platform {
+            Platform::MacOs => [(ctrl, "⌃"), (alt, "⌥"), (shift, "⇧"), (logo, "⌘")],
+            Platform::Windows => [
+                (ctrl, "Ctrl"),
+                (alt, "Alt"),
+                (shift, "Shift"),
+                (logo, "Win"),
+            ],
+            Platform::Linux => [
+                (ctrl, "Ctrl"),
+                (alt, "Alt"),
+                (shift, "Shift"),
+                (logo, "Super"),
+            ],
+        };
+        let separator = match self.platform {
+            Platform::MacOs => "",
+            _ => "+",
+        };
+
+        for (_, name) in names.iter().filter(|(held, _)| *held) {
+            write!(f, "{}{}", name, separator)?;
+        }
+        f.write_str(&self.shortcut.key.name())
+    }
+}
+
+/// An error returned when parsing a `Shortcut` fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseShortcutError {
+    /// The string was empty.
+    Empty,
+
+    /// The string ended in a `+` with no key.
+    MissingKey,
+
+    /// A modifier name was not recognized.
+    UnknownModifier(String),
+
+    /// The key name could not be parsed.
+    UnknownKey(String),
+}
+
+impl fmt::Display for ParseShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseShortcutError::Empty => write!(f, "empty shortcut"),
+            ParseShortcutError::MissingKey => write!(f, "shortcut has no key"),
+            ParseShortcutError::UnknownModifier(name) => write!(f, "unknown modifier `{}`", name),
+            ParseShortcutError::UnknownKey(name) => write!(f, "unknown key `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for ParseShortcutError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keyboard, KeyboardInterface};
+
+    fn ctrl_shift() -> Modifiers {
+        Modifiers {
+            ctrl: true,
+            shift: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_shortcut_with_modifiers() {
+        let shortcut: Shortcut<char> = "Ctrl+Shift+S".parse().unwrap();
+        assert_eq!(shortcut, Shortcut::new(ctrl_shift(), 'S'));
+    }
+
+    #[test]
+    fn parse_shortcut_with_plus_key() {
+        let shortcut: Shortcut<char> = "ctrl++".parse().unwrap();
+        assert_eq!(shortcut.key, '+');
+        assert!(shortcut.modifiers.ctrl);
+    }
+
+    #[test]
+    fn parse_shortcut_errors() {
+        assert_eq!("".parse::<Shortcut<char>>(), Err(ParseShortcutError::Empty));
+        assert_eq!(
+            "Hyper+S".parse::<Shortcut<char>>(),
+            Err(ParseShortcutError::UnknownModifier("Hyper".into()))
+        );
+        assert_eq!(
+            "Ctrl+Space".parse::<Shortcut<char>>(),
+            Err(ParseShortcutError::UnknownKey("Space".into()))
+        );
+    }
+
+    #[test]
+    fn display_shortcut_per_platform() {
+        let shortcut = Shortcut::new(ctrl_shift(), 'S');
+        assert_eq!(
+            shortcut.display_for(Platform::Linux).to_string(),
+            "Ctrl+Shift+S"
+        );
+        assert_eq!(shortcut.display_for(Platform::MacOs).to_string(), "⌃⇧S");
+    }
+
+    #[test]
+    fn shortcut_matches_exact_or_subset_modifiers() {
+        let mut keyboard: Keyboard<char, Modifiers> = Keyboard::new();
+        keyboard.press('S').set_modifiers(ctrl_shift());
+
+        let ctrl_s: Shortcut<char> = "Ctrl+S".parse().unwrap();
+        assert!(!ctrl_s.pressed(&keyboard, ModifierMatch::Exact));
+        assert!(ctrl_s.pressed(&keyboard, ModifierMatch::Subset));
+
+        let ctrl_shift_s: Shortcut<char> = "Ctrl+Shift+S".parse().unwrap();
+        assert!(ctrl_shift_s.pressed(&keyboard, ModifierMatch::Exact));
+    }
+}
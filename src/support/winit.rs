@@ -1,10 +1,21 @@
 //! Type aliases for input devices that work with `winit`.
 
+use std::borrow::Cow;
+
+use crate::names::{lookup_in, name_in};
 use crate::winit::{
     self,
-    event::{Event as WinitEvent, WindowEvent},
+    event::{DeviceEvent, Event as WinitEvent, WindowEvent},
+};
+use crate::{
+    Binding, Bindings, Capture, ConflictAnalyzer, EditKey, Event, InputLayers, InputSource,
+    KeyName, Keyboard, ModifierState, Modifiers, Mouse, Platform, PlayerSlots, Router, TextCommand,
+    Touchpad, prelude::*,
 };
-use crate::{Event, Keyboard, Mouse, Touchpad, prelude::*};
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+use crate::{KeyboardEvent, Preedit, Within};
+
+mod names;
 
 /// Alias for a type that represents a keyboard key code.
 #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
@@ -29,26 +40,788 @@ pub type WinitMouseButton = winit::event::MouseButton;
 pub type WinitKeyboard = Keyboard<WinitKey, WinitMods>;
 
 /// Alias for a `Mouse` that can represent `winit` mouse state.
+///
+/// Positions are in physical pixels. Initialize the scale factor from
+/// `Window::scale_factor` with `set_scale_factor`; later changes are applied
+/// from `WindowEvent::ScaleFactorChanged`.
+///
+/// Cursor events count as motion for `velocity`. After moving the cursor
+/// with `Window::set_cursor_position`, call `warp_to` so the jump doesn't.
 pub type WinitMouse = Mouse<WinitMouseButton, f64>;
 
 /// Alias for a `Touchpad` that can represent `winit` touch state.
+///
+/// Like `WinitMouse`, positions are in physical pixels, and the scale factor
+/// should be initialized with `set_scale_factor`.
 pub type WinitTouchpad = Touchpad<u64, f64>;
 
+/// Alias for a type that represents a physical key, including keys that
+/// `winit` could not identify.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+pub type WinitPhysicalKey = winit::keyboard::PhysicalKey;
+
+/// Alias for a type that represents a logical key, as produced by the
+/// current keyboard layout.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+pub type WinitLogicalKey = winit::keyboard::Key;
+
+/// Alias for a `Keyboard` that tracks physical keys.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+pub type WinitPhysicalKeyboard = Keyboard<WinitPhysicalKey, WinitMods>;
+
+/// Alias for a `Keyboard` that tracks logical keys.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+pub type WinitLogicalKeyboard = Keyboard<WinitLogicalKey, WinitMods>;
+
+/// Alias for a type that represents a hardware scancode.
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+pub type WinitScancode = winit::event::ScanCode;
+
+/// Alias for a `Keyboard` that tracks keys by scancode.
+///
+/// Unlike `WinitKeyboard`, this tracks every key, including those with no
+/// virtual key code (such as some media and international keys), and the
+/// scancodes don't depend on the keyboard layout.
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+pub type WinitScancodeKeyboard = Keyboard<WinitScancode, WinitMods>;
+
+/// Alias for a `Binding` to a `winit` key or mouse button.
+pub type WinitBinding = Binding<WinitKey, WinitMouseButton>;
+
+/// Alias for `Bindings` of actions to `winit` keys and mouse buttons.
+pub type WinitBindings = Bindings<WinitKey, WinitMouseButton>;
+
+/// Alias for a `Capture` that captures `winit` keys and mouse buttons.
+pub type WinitCapture = Capture<WinitKey, WinitMouseButton>;
+
+/// Alias for a `ConflictAnalyzer` over `winit` key and mouse button bindings.
+pub type WinitConflictAnalyzer<'a> = ConflictAnalyzer<'a, WinitKey, WinitMouseButton>;
+
+/// Alias for `InputLayers` over `winit` devices, with layers identified by `L`.
+pub type WinitInputLayers<L> = InputLayers<L, WinitKey, WinitMouseButton, u64>;
+
+/// The keys that `winit` reports as modifiers.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+pub const MODIFIER_KEYS: [WinitKey; 8] = [
+    WinitKey::ShiftLeft,
+    WinitKey::ShiftRight,
+    WinitKey::ControlLeft,
+    WinitKey::ControlRight,
+    WinitKey::AltLeft,
+    WinitKey::AltRight,
+    WinitKey::SuperLeft,
+    WinitKey::SuperRight,
+];
+
+/// The keys that `winit` reports as modifiers.
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+pub const MODIFIER_KEYS: [WinitKey; 8] = [
+    WinitKey::LShift,
+    WinitKey::RShift,
+    WinitKey::LControl,
+    WinitKey::RControl,
+    WinitKey::LAlt,
+    WinitKey::RAlt,
+    WinitKey::LWin,
+    WinitKey::RWin,
+];
+
 /// Create a new WinitKeyboard.
 pub fn keyboard() -> WinitKeyboard {
     WinitKeyboard::new()
 }
 
+/// Create a new WinitDualKeyboard.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+pub fn dual_keyboard() -> WinitDualKeyboard {
+    WinitDualKeyboard::new()
+}
+
+/// Create a new WinitScancodeKeyboard.
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+pub fn scancode_keyboard() -> WinitScancodeKeyboard {
+    WinitScancodeKeyboard::new()
+}
+
 /// Create a new WinitMouse.
 pub fn mouse() -> WinitMouse {
     WinitMouse::new()
 }
 
+/// Create a new WinitCapture which is cancelled by Escape and treats the
+/// `MODIFIER_KEYS` as modifiers.
+pub fn capture() -> WinitCapture {
+    WinitCapture::new()
+        .cancel_key(WinitKey::Escape)
+        .modifier_keys(MODIFIER_KEYS)
+}
+
+/// Create a new WinitConflictAnalyzer which knows the modifier flag set by
+/// each of the `MODIFIER_KEYS`.
+pub fn conflict_analyzer<'a>() -> WinitConflictAnalyzer<'a> {
+    let shift = Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    let ctrl = Modifiers {
+        ctrl: true,
+        ..Default::default()
+    };
+    let alt = Modifiers {
+        alt: true,
+        ..Default::default()
+    };
+    let logo = Modifiers {
+        logo: true,
+        ..Default::default()
+    };
+    let flags = [shift, shift, ctrl, ctrl, alt, alt, logo, logo];
+    MODIFIER_KEYS
+        .into_iter()
+        .zip(flags)
+        .fold(WinitConflictAnalyzer::new(), |analyzer, (key, flags)| {
+            analyzer.modifier_key(key, flags)
+        })
+}
+
 /// Create a new WinitMouse.
 pub fn touch() -> WinitTouchpad {
     WinitTouchpad::new()
 }
 
+/// Create a new WinitWindows.
+pub fn windows() -> WinitWindows {
+    WinitWindows::new()
+}
+
+/// The keyboard, mouse and touch state of a single window.
+#[derive(Debug, Clone, Default)]
+pub struct WinitDevices {
+    pub keyboard: WinitKeyboard,
+    pub mouse: WinitMouse,
+    pub touchpad: WinitTouchpad,
+}
+
+impl WinitDevices {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clears the pressed state of every device. Should be called at end of
+    /// frame.
+    pub fn clear_presses(&mut self) -> &mut Self {
+        self.keyboard.clear_presses();
+        self.mouse.clear_presses();
+        self.touchpad.clear_taps();
+        self
+    }
+
+    /// Convenience method for handling events.
+    pub fn handle_event<E: Event<Self>>(&mut self, event: &E) -> &mut Self {
+        event.handle(self);
+        self
+    }
+}
+
+/// Alias for a `Router` that keeps separate device state for each window,
+/// with a merged state for global shortcuts.
+///
+/// Raw mouse motion isn't tied to a window, so it goes to the window that
+/// was most recently focused.
+pub type WinitWindows = Router<winit::window::WindowId, WinitDevices>;
+
+/// Alias for a type that identifies a physical input device.
+pub type WinitDeviceId = winit::event::DeviceId;
+
+/// Alias for a `Router` that keeps separate state for each physical
+/// keyboard, with a merged state that matches a single `WinitKeyboard`.
+///
+/// Most platforms don't report separate device ids for keyboards, so there
+/// is usually only one route. Only key events create routes, so mice and
+/// touchscreens never appear here. Modifier changes and typed text have no
+/// device id, so they go to the keyboard that most recently sent a key.
+pub type WinitDeviceKeyboards = Router<WinitDeviceId, WinitKeyboard>;
+
+/// Alias for a `Router` that keeps separate state for each physical mouse,
+/// with a merged state that matches a single `WinitMouse`.
+///
+/// Only mouse button, wheel and cursor events create routes, so keyboards
+/// never appear here. Raw mouse motion only reaches mice that have already
+/// sent a window event, so other devices reporting motion don't get their
+/// own state.
+pub type WinitDeviceMice = Router<WinitDeviceId, WinitMouse>;
+
+/// Alias for `PlayerSlots` that assigns `winit` devices to players.
+///
+/// Handling `DeviceEvent::Removed` disconnects the removed device.
+pub type WinitPlayerSlots = PlayerSlots<WinitDeviceId>;
+
+/// Create a new WinitDeviceKeyboards.
+pub fn device_keyboards() -> WinitDeviceKeyboards {
+    WinitDeviceKeyboards::new()
+}
+
+/// Create a new WinitDeviceMice.
+pub fn device_mice() -> WinitDeviceMice {
+    WinitDeviceMice::new()
+}
+
+/// Returns the device that caused a window event. Events without a device
+/// (such as focus changes) apply to every device, except for keyboard
+/// events that are routed by `route_window_event`.
+fn event_device_id(event: &WindowEvent) -> Option<WinitDeviceId> {
+    match event {
+        WindowEvent::KeyboardInput { device_id, .. }
+        | WindowEvent::MouseInput { device_id, .. }
+        | WindowEvent::MouseWheel { device_id, .. }
+        | WindowEvent::CursorMoved { device_id, .. }
+        | WindowEvent::CursorEntered { device_id }
+        | WindowEvent::CursorLeft { device_id } => Some(*device_id),
+        WindowEvent::Touch(touch) => Some(touch.device_id),
+        _ => None,
+    }
+}
+
+/// Dispatch a window event to the device that caused it, focusing keyboards
+/// as they send keys. Events from other kinds of device only reach the
+/// merged state.
+fn route_window_event<D, E>(router: &mut Router<WinitDeviceId, D>, event: &WindowEvent, wrapper: &E)
+where
+    D: WinitRouted,
+    E: Event<D>,
+{
+    match event_device_id(event) {
+        Some(device_id) if D::sent_by_device(event) => {
+            if let WindowEvent::KeyboardInput { .. } = event {
+                router.set_focus(device_id);
+            }
+            router.dispatch(device_id, wrapper);
+        }
+        Some(_) => {
+            router.dispatch_merged(wrapper);
+        }
+        None => {
+            router.broadcast(wrapper);
+        }
+    }
+}
+
+/// Device state that `Router<WinitDeviceId, _>` can keep for each physical
+/// device.
+pub trait WinitRouted: Default {
+    /// Returns `true` if this kind of device sends the window event, so the
+    /// device that sent it should get its own state.
+    fn sent_by_device(event: &WindowEvent) -> bool;
+
+    /// Forget a removed device, releasing anything it was holding from the
+    /// merged state, unless another device is still holding it.
+    fn remove_device(router: &mut Router<WinitDeviceId, Self>, device_id: &WinitDeviceId);
+}
+
+fn remove_keyboard<K>(router: &mut Router<WinitDeviceId, K>, device_id: &WinitDeviceId)
+where
+    K: KeyboardInterface + Default,
+    K::Key: Clone,
+{
+    if let Some(removed) = router.remove(device_id) {
+        let held: Vec<_> = removed
+            .keys_down()
+            .filter(|key| router.iter().all(|(_, other)| !other.down(key)))
+            .cloned()
+            .collect();
+        for key in held {
+            router.merged_mut().release(key);
+        }
+    }
+}
+
+impl WinitRouted for WinitKeyboard {
+    fn sent_by_device(event: &WindowEvent) -> bool {
+        matches!(event, WindowEvent::KeyboardInput { .. })
+    }
+
+    fn remove_device(router: &mut Router<WinitDeviceId, Self>, device_id: &WinitDeviceId) {
+        remove_keyboard(router, device_id);
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl WinitRouted for WinitDualKeyboard {
+    fn sent_by_device(event: &WindowEvent) -> bool {
+        matches!(event, WindowEvent::KeyboardInput { .. })
+    }
+
+    fn remove_device(router: &mut Router<WinitDeviceId, Self>, device_id: &WinitDeviceId) {
+        remove_keyboard(router, device_id);
+    }
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+impl WinitRouted for WinitScancodeKeyboard {
+    fn sent_by_device(event: &WindowEvent) -> bool {
+        matches!(event, WindowEvent::KeyboardInput { .. })
+    }
+
+    fn remove_device(router: &mut Router<WinitDeviceId, Self>, device_id: &WinitDeviceId) {
+        remove_keyboard(router, device_id);
+    }
+}
+
+impl WinitRouted for WinitMouse {
+    fn sent_by_device(event: &WindowEvent) -> bool {
+        matches!(
+            event,
+            WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::CursorMoved { .. }
+                | WindowEvent::CursorEntered { .. }
+                | WindowEvent::CursorLeft { .. }
+        )
+    }
+
+    fn remove_device(router: &mut Router<WinitDeviceId, Self>, device_id: &WinitDeviceId) {
+        if let Some(removed) = router.remove(device_id) {
+            let held: Vec<_> = removed
+                .buttons_down()
+                .filter(|button| router.iter().all(|(_, other)| !other.down(button)))
+                .cloned()
+                .collect();
+            for button in held {
+                router.merged_mut().release(button);
+            }
+        }
+    }
+}
+
+impl WinitRouted for WinitTouchpad {
+    fn sent_by_device(event: &WindowEvent) -> bool {
+        matches!(event, WindowEvent::Touch(_))
+    }
+
+    fn remove_device(router: &mut Router<WinitDeviceId, Self>, device_id: &WinitDeviceId) {
+        if let Some(removed) = router.remove(device_id) {
+            for touch in removed.touches().filter(|t| !t.released) {
+                router.merged_mut().touch_event(
+                    touch.id,
+                    touch.position,
+                    crate::touch::TouchPhase::Cancel,
+                );
+            }
+        }
+    }
+}
+
+/// Disconnect a removed device from whichever player it was assigned to.
+fn disconnect_device(slots: &mut WinitPlayerSlots, device_id: WinitDeviceId) {
+    slots
+        .disconnect(&InputSource::Keyboard(device_id))
+        .disconnect(&InputSource::Mouse(device_id));
+}
+
+/// Tracks physical and logical keys side by side.
+///
+/// Physical keys identify where a key is on the keyboard, which is what
+/// movement controls such as WASD usually want. Logical keys identify what
+/// the key means in the current layout, so "press Q to quit" should check
+/// the logical key: on AZERTY layouts, the Q key is where QWERTY has A.
+///
+/// Keys that `winit` can't identify are kept as
+/// `PhysicalKey::Unidentified`, so they can still be bound by their native
+/// scancode.
+///
+/// Through `KeyboardInterface`, this behaves as its physical keyboard, so it
+/// works with `Bindings` and `Capture`. Keys pressed through the trait have
+/// no logical key, so use `press_key` to update both.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+#[derive(Debug, Clone, Default)]
+pub struct WinitDualKeyboard {
+    physical: WinitPhysicalKeyboard,
+    logical: WinitLogicalKeyboard,
+    held: Vec<(WinitPhysicalKey, WinitLogicalKey)>,
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl WinitDualKeyboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the keyboard state keyed by physical key.
+    pub fn physical(&self) -> &WinitPhysicalKeyboard {
+        &self.physical
+    }
+
+    /// Returns the keyboard state keyed by logical key.
+    pub fn logical(&self) -> &WinitLogicalKeyboard {
+        &self.logical
+    }
+
+    /// Returns `true` if a key producing the given character is held down.
+    ///
+    /// Characters are compared ignoring case, so `"q"` matches whether or
+    /// not Shift or Caps Lock is on.
+    pub fn character_down(&self, ch: &str) -> bool {
+        self.logical.keys_down().any(|k| is_character(k, ch))
+    }
+
+    /// Returns `true` if a key producing the given character was pressed
+    /// this frame.
+    pub fn character_pressed(&self, ch: &str) -> bool {
+        self.logical.keys_pressed().any(|k| is_character(k, ch))
+    }
+
+    /// Returns `true` if a key producing the given character was released
+    /// this frame.
+    pub fn character_released(&self, ch: &str) -> bool {
+        self.logical.keys_released().any(|k| is_character(k, ch))
+    }
+
+    /// Register that a key was pressed down.
+    pub fn press_key(&mut self, physical: WinitPhysicalKey, logical: WinitLogicalKey) -> &mut Self {
+        self.held.retain(|(p, _)| p != &physical);
+        self.held.push((physical, logical.clone()));
+        self.physical.press(physical);
+        self.logical.press(logical);
+        self
+    }
+
+    /// Register that a key was released.
+    ///
+    /// The logical key released is the one that was pressed, even if the
+    /// layout or modifiers have since changed what the key produces.
+    pub fn release_key(
+        &mut self,
+        physical: WinitPhysicalKey,
+        logical: WinitLogicalKey,
+    ) -> &mut Self {
+        let logical = match self.held.iter().position(|(p, _)| p == &physical) {
+            Some(index) => self.held.remove(index).1,
+            None => logical,
+        };
+        self.physical.release(physical);
+        if !self.held.iter().any(|(_, l)| l == &logical) {
+            self.logical.release(logical);
+        }
+        self
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl KeyboardQuery for WinitDualKeyboard {
+    type Key = WinitPhysicalKey;
+    type Mods = WinitMods;
+
+    fn modifiers(&self) -> Option<&WinitMods> {
+        self.physical.modifiers()
+    }
+
+    fn down(&self, key: &WinitPhysicalKey) -> bool {
+        self.physical.down(key)
+    }
+
+    fn pressed(&self, key: &WinitPhysicalKey) -> bool {
+        self.physical.pressed(key)
+    }
+
+    fn released(&self, key: &WinitPhysicalKey) -> bool {
+        self.physical.released(key)
+    }
+
+    fn keys_down(&self) -> impl Iterator<Item = &WinitPhysicalKey> {
+        self.physical.keys_down()
+    }
+
+    fn keys_pressed(&self) -> impl Iterator<Item = &WinitPhysicalKey> {
+        self.physical.keys_pressed()
+    }
+
+    fn keys_released(&self) -> impl Iterator<Item = &WinitPhysicalKey> {
+        self.physical.keys_released()
+    }
+
+    fn pressed_within<W: Into<Within>>(&self, key: &WinitPhysicalKey, within: W) -> bool {
+        self.physical.pressed_within(key, within)
+    }
+
+    fn released_within<W: Into<Within>>(&self, key: &WinitPhysicalKey, within: W) -> bool {
+        self.physical.released_within(key, within)
+    }
+
+    fn text(&self) -> &str {
+        self.physical.text()
+    }
+
+    fn text_commands(&self) -> impl Iterator<Item = &TextCommand> {
+        self.physical.text_commands()
+    }
+
+    fn events(&self) -> impl Iterator<Item = &KeyboardEvent<WinitPhysicalKey>> {
+        self.physical.events()
+    }
+
+    fn preedit(&self) -> Option<&Preedit> {
+        self.physical.preedit()
+    }
+
+    fn ime_commits(&self) -> impl Iterator<Item = &str> {
+        self.physical.ime_commits()
+    }
+
+    fn ime_enabled(&self) -> bool {
+        self.physical.ime_enabled()
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl KeyboardInterface for WinitDualKeyboard {
+    fn clear_presses(&mut self) -> &mut Self {
+        self.physical.clear_presses();
+        self.logical.clear_presses();
+        self
+    }
+
+    fn press(&mut self, key: WinitPhysicalKey) -> &mut Self {
+        self.physical.press(key);
+        self
+    }
+
+    fn release(&mut self, key: WinitPhysicalKey) -> &mut Self {
+        match self.held.iter().find(|(p, _)| p == &key) {
+            Some((_, logical)) => {
+                let logical = logical.clone();
+                self.release_key(key, logical)
+            }
+            None => {
+                self.physical.release(key);
+                self
+            }
+        }
+    }
+
+    fn release_all(&mut self) -> &mut Self {
+        self.held.clear();
+        self.physical.release_all();
+        self.logical.release_all();
+        self
+    }
+
+    fn set_modifiers(&mut self, modifiers: WinitMods) -> &mut Self {
+        self.physical.set_modifiers(modifiers);
+        self.logical.set_modifiers(modifiers);
+        self
+    }
+
+    fn receive_text<S: AsRef<str>>(&mut self, text: S) -> &mut Self {
+        self.physical.receive_text(&text);
+        self.logical.receive_text(&text);
+        self
+    }
+
+    fn receive_char(&mut self, ch: char) -> &mut Self {
+        self.physical.receive_char(ch);
+        self.logical.receive_char(ch);
+        self
+    }
+
+    fn push_text_command(&mut self, command: TextCommand) -> &mut Self {
+        self.physical.push_text_command(command);
+        self.logical.push_text_command(command);
+        self
+    }
+
+    fn set_preedit<S: AsRef<str>>(&mut self, text: S, cursor: Option<(usize, usize)>) -> &mut Self {
+        self.physical.set_preedit(&text, cursor);
+        self.logical.set_preedit(&text, cursor);
+        self
+    }
+
+    fn commit_text<S: AsRef<str>>(&mut self, text: S) -> &mut Self {
+        self.physical.commit_text(&text);
+        self.logical.commit_text(&text);
+        self
+    }
+
+    fn set_ime_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.physical.set_ime_enabled(enabled);
+        self.logical.set_ime_enabled(enabled);
+        self
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+fn is_character(key: &WinitLogicalKey, ch: &str) -> bool {
+    matches!(key, WinitLogicalKey::Character(c) if c.to_lowercase() == ch.to_lowercase())
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl ModifierState for WinitMods {
+    fn to_modifiers(&self) -> Modifiers {
+        let state = self.state();
+        Modifiers {
+            ctrl: state.control_key(),
+            alt: state.alt_key(),
+            shift: state.shift_key(),
+            logo: state.super_key(),
+        }
+    }
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+impl ModifierState for WinitMods {
+    fn to_modifiers(&self) -> Modifiers {
+        Modifiers {
+            ctrl: self.ctrl(),
+            alt: self.alt(),
+            shift: self.shift(),
+            logo: self.logo(),
+        }
+    }
+}
+
+impl KeyName for WinitKey {
+    fn name(&self) -> Cow<'static, str> {
+        match name_in(names::KEYS.iter().map(|(k, n)| (k, *n)), self) {
+            Some(name) => Cow::Borrowed(name),
+            None => Cow::Owned(format!("{:?}", self)),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let name = lookup_in(names::ALIASES.iter().map(|(a, c)| (c, *a)), name)
+            .copied()
+            .unwrap_or(name);
+        lookup_in(names::KEYS.iter().map(|(k, n)| (k, *n)), name).copied()
+    }
+}
+
+impl KeyName for WinitMouseButton {
+    fn name(&self) -> Cow<'static, str> {
+        match self {
+            WinitMouseButton::Left => Cow::Borrowed("MouseLeft"),
+            WinitMouseButton::Right => Cow::Borrowed("MouseRight"),
+            WinitMouseButton::Middle => Cow::Borrowed("MouseMiddle"),
+            #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+            WinitMouseButton::Back => Cow::Borrowed("MouseBack"),
+            #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+            WinitMouseButton::Forward => Cow::Borrowed("MouseForward"),
+            WinitMouseButton::Other(n) => Cow::Owned(format!("Mouse{}", n)),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        let button = name.strip_prefix("mouse")?;
+        match button {
+            "left" => Some(WinitMouseButton::Left),
+            "right" => Some(WinitMouseButton::Right),
+            "middle" => Some(WinitMouseButton::Middle),
+            #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+            "back" => Some(WinitMouseButton::Back),
+            #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+            "forward" => Some(WinitMouseButton::Forward),
+            n => n.parse().ok().map(WinitMouseButton::Other),
+        }
+    }
+}
+
+/// Returns the text editing command triggered by pressing `key` with the
+/// given modifiers held, following the current platform's conventions.
+fn text_command(key: EditKey, modifiers: Option<&WinitMods>) -> Option<TextCommand> {
+    let modifiers = modifiers
+        .map(ModifierState::to_modifiers)
+        .unwrap_or_default();
+    TextCommand::for_key(key, modifiers, Platform::current())
+}
+
+/// Returns the editing key for a key event. Letters are read from the
+/// logical key, so shortcuts such as Ctrl+Z follow the keyboard layout (on
+/// AZERTY, Ctrl+Z is where QWERTY has Ctrl+W). The physical key is only used
+/// when the logical key isn't a Latin character, as on Cyrillic layouts.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+fn event_edit_key(physical: &WinitPhysicalKey, logical: &WinitLogicalKey) -> Option<EditKey> {
+    use winit::keyboard::{NamedKey, PhysicalKey};
+
+    match logical {
+        WinitLogicalKey::Named(named) => Some(match named {
+            NamedKey::Backspace => EditKey::Backspace,
+            NamedKey::Delete => EditKey::Delete,
+            NamedKey::Insert => EditKey::Insert,
+            NamedKey::ArrowLeft => EditKey::Left,
+            NamedKey::ArrowRight => EditKey::Right,
+            NamedKey::ArrowUp => EditKey::Up,
+            NamedKey::ArrowDown => EditKey::Down,
+            NamedKey::Home => EditKey::Home,
+            NamedKey::End => EditKey::End,
+            NamedKey::Enter => EditKey::Enter,
+            NamedKey::Tab => EditKey::Tab,
+            _ => return None,
+        }),
+        WinitLogicalKey::Character(ch) if ch.is_ascii() => {
+            Some(match ch.to_ascii_lowercase().as_str() {
+                "a" => EditKey::A,
+                "c" => EditKey::C,
+                "v" => EditKey::V,
+                "x" => EditKey::X,
+                "y" => EditKey::Y,
+                "z" => EditKey::Z,
+                _ => return None,
+            })
+        }
+        _ => match physical {
+            PhysicalKey::Code(code) => edit_key(code),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+fn edit_key(key: &WinitKey) -> Option<EditKey> {
+    Some(match key {
+        WinitKey::Backspace => EditKey::Backspace,
+        WinitKey::Delete => EditKey::Delete,
+        WinitKey::Insert => EditKey::Insert,
+        WinitKey::ArrowLeft => EditKey::Left,
+        WinitKey::ArrowRight => EditKey::Right,
+        WinitKey::ArrowUp => EditKey::Up,
+        WinitKey::ArrowDown => EditKey::Down,
+        WinitKey::Home => EditKey::Home,
+        WinitKey::End => EditKey::End,
+        WinitKey::Enter | WinitKey::NumpadEnter => EditKey::Enter,
+        WinitKey::Tab => EditKey::Tab,
+        WinitKey::KeyA => EditKey::A,
+        WinitKey::KeyC => EditKey::C,
+        WinitKey::KeyV => EditKey::V,
+        WinitKey::KeyX => EditKey::X,
+        WinitKey::KeyY => EditKey::Y,
+        WinitKey::KeyZ => EditKey::Z,
+        _ => return None,
+    })
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+fn edit_key(key: &WinitKey) -> Option<EditKey> {
+    Some(match key {
+        WinitKey::Back => EditKey::Backspace,
+        WinitKey::Delete => EditKey::Delete,
+        WinitKey::Insert => EditKey::Insert,
+        WinitKey::Left => EditKey::Left,
+        WinitKey::Right => EditKey::Right,
+        WinitKey::Up => EditKey::Up,
+        WinitKey::Down => EditKey::Down,
+        WinitKey::Home => EditKey::Home,
+        WinitKey::End => EditKey::End,
+        WinitKey::Return | WinitKey::NumpadEnter => EditKey::Enter,
+        WinitKey::Tab => EditKey::Tab,
+        WinitKey::A => EditKey::A,
+        WinitKey::C => EditKey::C,
+        WinitKey::V => EditKey::V,
+        WinitKey::X => EditKey::X,
+        WinitKey::Y => EditKey::Y,
+        WinitKey::Z => EditKey::Z,
+        _ => return None,
+    })
+}
+
 // winit >= 0.29 event handlers
 #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
 impl<T> Event<WinitKeyboard> for WinitEvent<T> {
@@ -61,6 +834,7 @@ impl<T> Event<WinitKeyboard> for WinitEvent<T> {
                     let winit::event::KeyEvent {
                         state,
                         physical_key,
+                        logical_key,
                         text,
                         ..
                     } = event;
@@ -70,6 +844,12 @@ impl<T> Event<WinitKeyboard> for WinitEvent<T> {
                             ElementState::Released => keyboard.release(*code),
                         };
                     }
+                    if *state == ElementState::Pressed
+                        && let Some(key) = event_edit_key(physical_key, logical_key)
+                        && let Some(command) = text_command(key, keyboard.modifiers())
+                    {
+                        keyboard.push_text_command(command);
+                    }
 
                     if let Some(text) = text {
                         if !text.is_empty() {
@@ -80,15 +860,89 @@ impl<T> Event<WinitKeyboard> for WinitEvent<T> {
                 WindowEvent::ModifiersChanged(state) => {
                     keyboard.set_modifiers(*state);
                 }
+                WindowEvent::Ime(ime) => handle_ime(keyboard, ime),
+                WindowEvent::Focused(false) => {
+                    keyboard.release_all();
+                }
                 _ => (),
             }
         }
     }
 }
 
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl<T> Event<WinitDualKeyboard> for WinitEvent<T> {
+    fn handle(&self, keyboard: &mut WinitDualKeyboard) {
+        if let WinitEvent::WindowEvent { event, .. } = self {
+            use winit::event::ElementState;
+
+            match event {
+                WindowEvent::KeyboardInput { event, .. } => {
+                    let winit::event::KeyEvent {
+                        state,
+                        physical_key,
+                        logical_key,
+                        text,
+                        ..
+                    } = event;
+                    match state {
+                        ElementState::Pressed => {
+                            keyboard.press_key(*physical_key, logical_key.clone())
+                        }
+                        ElementState::Released => {
+                            keyboard.release_key(*physical_key, logical_key.clone())
+                        }
+                    };
+                    if *state == ElementState::Pressed
+                        && let Some(key) = event_edit_key(physical_key, logical_key)
+                        && let Some(command) = text_command(key, keyboard.modifiers())
+                    {
+                        keyboard.push_text_command(command);
+                    }
+
+                    if let Some(text) = text
+                        && !text.is_empty()
+                    {
+                        keyboard.receive_text(text);
+                    }
+                }
+                WindowEvent::ModifiersChanged(state) => {
+                    keyboard.set_modifiers(*state);
+                }
+                WindowEvent::Ime(ime) => {
+                    handle_ime(keyboard, ime);
+                }
+                WindowEvent::Focused(false) => {
+                    keyboard.release_all();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+fn handle_ime<K: KeyboardInterface>(keyboard: &mut K, ime: &winit::event::Ime) {
+    use winit::event::Ime;
+
+    match ime {
+        Ime::Enabled => keyboard.set_ime_enabled(true),
+        Ime::Preedit(text, cursor) => keyboard.set_preedit(text, *cursor),
+        Ime::Commit(text) => keyboard.commit_text(text),
+        Ime::Disabled => keyboard.set_ime_enabled(false),
+    };
+}
+
 #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
 impl<T> Event<WinitMouse> for WinitEvent<T> {
     fn handle(&self, mouse: &mut WinitMouse) {
+        if let WinitEvent::DeviceEvent {
+            event: DeviceEvent::MouseMotion { delta },
+            ..
+        } = self
+        {
+            mouse.add_motion_delta([delta.0, delta.1]);
+        }
         if let WinitEvent::WindowEvent { event, .. } = self {
             {
                 use winit::event::ElementState;
@@ -103,6 +957,18 @@ impl<T> Event<WinitMouse> for WinitEvent<T> {
                     WindowEvent::CursorMoved { position, .. } => {
                         mouse.move_to([position.x, position.y]);
                     }
+                    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                        mouse.scale_factor_changed(*scale_factor);
+                    }
+                    WindowEvent::CursorEntered { .. } => {
+                        mouse.enter_window();
+                    }
+                    WindowEvent::CursorLeft { .. } => {
+                        mouse.leave_window();
+                    }
+                    WindowEvent::Focused(false) => {
+                        mouse.release_all();
+                    }
                     _ => (),
                 }
             }
@@ -117,22 +983,120 @@ impl<T> Event<Touchpad<u64, f64>> for WinitEvent<T> {
             {
                 use winit::event::TouchPhase;
 
-                if let WindowEvent::Touch(touch) = event {
-                    let pos = [touch.location.x, touch.location.y];
-                    let phase = match touch.phase {
-                        TouchPhase::Started => crate::touch::TouchPhase::Start,
-                        TouchPhase::Ended => crate::touch::TouchPhase::End,
-                        TouchPhase::Moved => crate::touch::TouchPhase::Move,
-                        TouchPhase::Cancelled => crate::touch::TouchPhase::Cancel,
-                    };
-                    touchpad.touch_event(touch.id, pos, phase);
+                match event {
+                    WindowEvent::Touch(touch) => {
+                        let pos = [touch.location.x, touch.location.y];
+                        let phase = match touch.phase {
+                            TouchPhase::Started => crate::touch::TouchPhase::Start,
+                            TouchPhase::Ended => crate::touch::TouchPhase::End,
+                            TouchPhase::Moved => crate::touch::TouchPhase::Move,
+                            TouchPhase::Cancelled => crate::touch::TouchPhase::Cancel,
+                        };
+                        touchpad.touch_event(touch.id, pos, phase);
+                    }
+                    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                        touchpad.scale_factor_changed(*scale_factor);
+                    }
+                    WindowEvent::Focused(false) => {
+                        touchpad.release_all();
+                    }
+                    _ => (),
                 }
             }
         }
     }
 }
 
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl<T> Event<WinitDevices> for WinitEvent<T> {
+    fn handle(&self, devices: &mut WinitDevices) {
+        devices.keyboard.handle_event(self);
+        devices.mouse.handle_event(self);
+        devices.touchpad.handle_event(self);
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl<T> Event<WinitWindows> for WinitEvent<T> {
+    fn handle(&self, windows: &mut WinitWindows) {
+        match self {
+            WinitEvent::WindowEvent { window_id, event } => {
+                if let WindowEvent::Focused(true) = event {
+                    windows.set_focus(*window_id);
+                }
+                windows.dispatch(*window_id, self);
+            }
+            WinitEvent::DeviceEvent {
+                event: DeviceEvent::MouseMotion { .. },
+                ..
+            } => {
+                windows.dispatch_focused(self);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl<T, D> Event<Router<WinitDeviceId, D>> for WinitEvent<T>
+where
+    D: WinitRouted,
+    WinitEvent<T>: Event<D>,
+{
+    fn handle(&self, router: &mut Router<WinitDeviceId, D>) {
+        match self {
+            WinitEvent::WindowEvent { event, .. } => match event {
+                WindowEvent::ModifiersChanged(_) | WindowEvent::Ime(_) => {
+                    router.dispatch_focused(self);
+                }
+                _ => route_window_event(router, event, self),
+            },
+            WinitEvent::DeviceEvent {
+                device_id,
+                event: DeviceEvent::MouseMotion { .. },
+            } => {
+                if router.get(device_id).is_some() {
+                    router.dispatch(*device_id, self);
+                } else {
+                    router.dispatch_merged(self);
+                }
+            }
+            WinitEvent::DeviceEvent {
+                device_id,
+                event: DeviceEvent::Removed,
+            } => {
+                D::remove_device(router, device_id);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+impl<T> Event<WinitPlayerSlots> for WinitEvent<T> {
+    fn handle(&self, slots: &mut WinitPlayerSlots) {
+        if let WinitEvent::DeviceEvent {
+            device_id,
+            event: DeviceEvent::Removed,
+        } = self
+        {
+            disconnect_device(slots, *device_id);
+        }
+    }
+}
+
 // winit < 0.29 event handlers
+
+/// Before `WindowEvent::ModifiersChanged` was added in winit 0.22, modifier
+/// state only arrived with each key event, so read it from there.
+#[cfg(feature = "winit_0_21")]
+fn set_input_modifiers<K>(keyboard: &mut K, input: &winit::event::KeyboardInput)
+where
+    K: KeyboardInterface<Mods = WinitMods>,
+{
+    #[allow(deprecated)]
+    keyboard.set_modifiers(input.modifiers);
+}
 #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
 impl<T> Event<WinitKeyboard> for WinitEvent<'_, T> {
     fn handle(&self, keyboard: &mut WinitKeyboard) {
@@ -146,11 +1110,65 @@ impl<T> Event<WinitKeyboard> for WinitEvent<'_, T> {
                         virtual_keycode,
                         ..
                     } = input;
+                    #[cfg(feature = "winit_0_21")]
+                    set_input_modifiers(keyboard, input);
                     if let Some(vkc) = virtual_keycode {
                         match state {
                             ElementState::Pressed => keyboard.press(*vkc),
                             ElementState::Released => keyboard.release(*vkc),
                         };
+                        if *state == ElementState::Pressed
+                            && let Some(key) = edit_key(vkc)
+                            && let Some(command) = text_command(key, keyboard.modifiers())
+                        {
+                            keyboard.push_text_command(command);
+                        }
+                    }
+                }
+
+                WindowEvent::ReceivedCharacter(ch) => {
+                    keyboard.receive_char(*ch);
+                }
+
+                #[cfg(not(feature = "winit_0_21"))]
+                WindowEvent::ModifiersChanged(state) => {
+                    keyboard.set_modifiers(*state);
+                }
+                WindowEvent::Focused(false) => {
+                    keyboard.release_all();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+impl<T> Event<WinitScancodeKeyboard> for WinitEvent<'_, T> {
+    fn handle(&self, keyboard: &mut WinitScancodeKeyboard) {
+        if let WinitEvent::WindowEvent { event, .. } = self {
+            use winit::event::ElementState;
+
+            match event {
+                WindowEvent::KeyboardInput { input, .. } => {
+                    let winit::event::KeyboardInput {
+                        state,
+                        scancode,
+                        virtual_keycode,
+                        ..
+                    } = input;
+                    #[cfg(feature = "winit_0_21")]
+                    set_input_modifiers(keyboard, input);
+                    match state {
+                        ElementState::Pressed => keyboard.press(*scancode),
+                        ElementState::Released => keyboard.release(*scancode),
+                    };
+                    if *state == ElementState::Pressed
+                        && let Some(vkc) = virtual_keycode
+                        && let Some(key) = edit_key(vkc)
+                        && let Some(command) = text_command(key, keyboard.modifiers())
+                    {
+                        keyboard.push_text_command(command);
                     }
                 }
 
@@ -162,6 +1180,9 @@ impl<T> Event<WinitKeyboard> for WinitEvent<'_, T> {
                 WindowEvent::ModifiersChanged(state) => {
                     keyboard.set_modifiers(*state);
                 }
+                WindowEvent::Focused(false) => {
+                    keyboard.release_all();
+                }
                 _ => (),
             }
         }
@@ -171,6 +1192,13 @@ impl<T> Event<WinitKeyboard> for WinitEvent<'_, T> {
 #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
 impl<T> Event<WinitMouse> for WinitEvent<'_, T> {
     fn handle(&self, mouse: &mut WinitMouse) {
+        if let WinitEvent::DeviceEvent {
+            event: DeviceEvent::MouseMotion { delta },
+            ..
+        } = self
+        {
+            mouse.add_motion_delta([delta.0, delta.1]);
+        }
         if let WinitEvent::WindowEvent { event, .. } = self {
             {
                 use winit::event::ElementState;
@@ -185,6 +1213,18 @@ impl<T> Event<WinitMouse> for WinitEvent<'_, T> {
                     WindowEvent::CursorMoved { position, .. } => {
                         mouse.move_to([position.x, position.y]);
                     }
+                    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                        mouse.scale_factor_changed(*scale_factor);
+                    }
+                    WindowEvent::CursorEntered { .. } => {
+                        mouse.enter_window();
+                    }
+                    WindowEvent::CursorLeft { .. } => {
+                        mouse.leave_window();
+                    }
+                    WindowEvent::Focused(false) => {
+                        mouse.release_all();
+                    }
                     _ => (),
                 }
             }
@@ -199,17 +1239,108 @@ impl<T> Event<Touchpad<u64, f64>> for WinitEvent<'_, T> {
             {
                 use winit::event::TouchPhase;
 
-                if let WindowEvent::Touch(touch) = event {
-                    let pos = [touch.location.x, touch.location.y];
-                    let phase = match touch.phase {
-                        TouchPhase::Started => crate::touch::TouchPhase::Start,
-                        TouchPhase::Ended => crate::touch::TouchPhase::End,
-                        TouchPhase::Moved => crate::touch::TouchPhase::Move,
-                        TouchPhase::Cancelled => crate::touch::TouchPhase::Cancel,
-                    };
-                    touchpad.touch_event(touch.id, pos, phase);
+                match event {
+                    WindowEvent::Touch(touch) => {
+                        let pos = [touch.location.x, touch.location.y];
+                        let phase = match touch.phase {
+                            TouchPhase::Started => crate::touch::TouchPhase::Start,
+                            TouchPhase::Ended => crate::touch::TouchPhase::End,
+                            TouchPhase::Moved => crate::touch::TouchPhase::Move,
+                            TouchPhase::Cancelled => crate::touch::TouchPhase::Cancel,
+                        };
+                        touchpad.touch_event(touch.id, pos, phase);
+                    }
+                    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                        touchpad.scale_factor_changed(*scale_factor);
+                    }
+                    WindowEvent::Focused(false) => {
+                        touchpad.release_all();
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+impl<T> Event<WinitDevices> for WinitEvent<'_, T> {
+    fn handle(&self, devices: &mut WinitDevices) {
+        devices.keyboard.handle_event(self);
+        devices.mouse.handle_event(self);
+        devices.touchpad.handle_event(self);
+    }
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+impl<T> Event<WinitWindows> for WinitEvent<'_, T> {
+    fn handle(&self, windows: &mut WinitWindows) {
+        match self {
+            WinitEvent::WindowEvent { window_id, event } => {
+                if let WindowEvent::Focused(true) = event {
+                    windows.set_focus(*window_id);
                 }
+                windows.dispatch(*window_id, self);
+            }
+            WinitEvent::DeviceEvent {
+                event: DeviceEvent::MouseMotion { .. },
+                ..
+            } => {
+                windows.dispatch_focused(self);
             }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+impl<'a, T, D> Event<Router<WinitDeviceId, D>> for WinitEvent<'a, T>
+where
+    D: WinitRouted,
+    WinitEvent<'a, T>: Event<D>,
+{
+    fn handle(&self, router: &mut Router<WinitDeviceId, D>) {
+        match self {
+            WinitEvent::WindowEvent { event, .. } => match event {
+                #[cfg(not(feature = "winit_0_21"))]
+                WindowEvent::ModifiersChanged(_) => {
+                    router.dispatch_focused(self);
+                }
+                WindowEvent::ReceivedCharacter(_) => {
+                    router.dispatch_focused(self);
+                }
+                _ => route_window_event(router, event, self),
+            },
+            WinitEvent::DeviceEvent {
+                device_id,
+                event: DeviceEvent::MouseMotion { .. },
+            } => {
+                if router.get(device_id).is_some() {
+                    router.dispatch(*device_id, self);
+                } else {
+                    router.dispatch_merged(self);
+                }
+            }
+            WinitEvent::DeviceEvent {
+                device_id,
+                event: DeviceEvent::Removed,
+            } => {
+                D::remove_device(router, device_id);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+impl<T> Event<WinitPlayerSlots> for WinitEvent<'_, T> {
+    fn handle(&self, slots: &mut WinitPlayerSlots) {
+        if let WinitEvent::DeviceEvent {
+            device_id,
+            event: DeviceEvent::Removed,
+        } = self
+        {
+            disconnect_device(slots, *device_id);
         }
     }
 }
@@ -250,7 +1381,7 @@ mod tests {
         };
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::ModifiersChanged(state),
             }
         }
@@ -258,6 +1389,15 @@ mod tests {
 
     #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
     fn make_keyboard_event(pressed: bool, key: WinitKey) -> WinitEvent<'static, ()> {
+        make_scancode_event(pressed, 0, Some(key))
+    }
+
+    #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+    fn make_scancode_event(
+        pressed: bool,
+        scancode: WinitScancode,
+        key: Option<WinitKey>,
+    ) -> WinitEvent<'static, ()> {
         let state = match pressed {
             true => ElementState::Pressed,
             false => ElementState::Released,
@@ -265,13 +1405,13 @@ mod tests {
 
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::KeyboardInput {
-                    device_id: ::std::mem::uninitialized(),
+                    device_id: WinitDeviceId::dummy(),
                     input: winit::event::KeyboardInput {
-                        scancode: 0,
+                        scancode,
                         state,
-                        virtual_keycode: Some(key),
+                        virtual_keycode: key,
                         modifiers: Default::default(),
                     },
                     is_synthetic: false,
@@ -281,6 +1421,7 @@ mod tests {
     }
 
     #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+    #[allow(unused_unsafe)]
     fn make_mouse_button_event(pressed: bool, button: WinitMouseButton) -> WinitEvent<()> {
         let state = match pressed {
             true => ElementState::Pressed,
@@ -289,9 +1430,9 @@ mod tests {
 
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::MouseInput {
-                    device_id: ::std::mem::uninitialized(),
+                    device_id: WinitDeviceId::dummy(),
                     state,
                     button,
 
@@ -311,9 +1452,9 @@ mod tests {
 
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::MouseInput {
-                    device_id: ::std::mem::uninitialized(),
+                    device_id: WinitDeviceId::dummy(),
                     state,
                     button,
 
@@ -325,12 +1466,13 @@ mod tests {
     }
 
     #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+    #[allow(unused_unsafe)]
     fn make_touch_event(id: u64, pos: [f64; 2], phase: TP) -> WinitEvent<()> {
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::Touch(winit::event::Touch {
-                    device_id: ::std::mem::uninitialized(),
+                    device_id: WinitDeviceId::dummy(),
                     phase,
                     location: winit::dpi::PhysicalPosition::new(pos[0], pos[1]),
                     force: None,
@@ -344,9 +1486,9 @@ mod tests {
     fn make_touch_event(id: u64, pos: [f64; 2], phase: TP) -> WinitEvent<'static, ()> {
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::Touch(winit::event::Touch {
-                    device_id: ::std::mem::uninitialized(),
+                    device_id: WinitDeviceId::dummy(),
                     phase,
                     location: winit::dpi::PhysicalPosition::new(pos[0], pos[1]),
                     force: None,
@@ -357,15 +1499,16 @@ mod tests {
     }
 
     #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+    #[allow(unused_unsafe)]
     fn make_cursor_event(position: [f64; 2]) -> WinitEvent<()> {
         use winit::dpi::PhysicalPosition;
 
         let [x, y] = position;
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::CursorMoved {
-                    device_id: ::std::mem::uninitialized(),
+                    device_id: WinitDeviceId::dummy(),
                     position: PhysicalPosition { x, y },
                 },
             }
@@ -379,9 +1522,9 @@ mod tests {
         let [x, y] = position;
         unsafe {
             WinitEvent::WindowEvent {
-                window_id: ::std::mem::uninitialized(),
+                window_id: winit::window::WindowId::dummy(),
                 event: WindowEvent::CursorMoved {
-                    device_id: ::std::mem::uninitialized(),
+                    device_id: WinitDeviceId::dummy(),
                     position: PhysicalPosition { x, y },
                     modifiers: WinitMods::default(),
                 },
@@ -409,6 +1552,19 @@ mod tests {
         assert!(keyboard.released(&TEST_KEY));
     }
 
+    #[test]
+    #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+    fn unmapped_scancode_press_via_event() {
+        let event = make_scancode_event(true, 0xe0, None);
+        let mut keyboard = keyboard();
+        let mut scancode_keyboard = scancode_keyboard();
+        keyboard.handle_event(&event);
+        scancode_keyboard.handle_event(&event);
+
+        assert!(keyboard.keys_pressed().next().is_none());
+        assert!(scancode_keyboard.pressed(&0xe0));
+    }
+
     #[test]
     #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
     fn modifiers_via_event() {
@@ -458,6 +1614,82 @@ mod tests {
         assert_eq!(mouse.position(), [1., 1.]);
     }
 
+    #[test]
+    #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+    fn edit_keys_follow_the_layout() {
+        use winit::keyboard::PhysicalKey;
+
+        let key = |code, ch: &str| {
+            event_edit_key(
+                &PhysicalKey::Code(code),
+                &WinitLogicalKey::Character(ch.into()),
+            )
+        };
+        assert_eq!(key(WinitKey::KeyW, "z"), Some(EditKey::Z));
+        assert_eq!(key(WinitKey::KeyZ, "w"), None);
+        assert_eq!(key(WinitKey::KeyQ, "A"), Some(EditKey::A));
+        assert_eq!(key(WinitKey::KeyC, "\u{441}"), Some(EditKey::C));
+    }
+
+    #[test]
+    #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+    fn dual_keyboard_releases_the_logical_key_that_was_pressed() {
+        use winit::keyboard::PhysicalKey;
+
+        let mut keyboard = dual_keyboard();
+        let physical = PhysicalKey::Code(WinitKey::KeyA);
+        keyboard.press_key(physical, WinitLogicalKey::Character("q".into()));
+        assert!(keyboard.physical().pressed(&physical));
+        assert!(keyboard.character_pressed("q"));
+        assert!(keyboard.character_pressed("Q"));
+
+        keyboard.clear_presses();
+        keyboard.release_key(physical, WinitLogicalKey::Character("Q".into()));
+        assert!(keyboard.character_released("q"));
+        assert!(!keyboard.character_down("q"));
+        assert!(!keyboard.physical().down(&physical));
+
+        let jump: crate::Shortcut<WinitPhysicalKey> = crate::Shortcut::key(physical);
+        keyboard.clear_presses();
+        keyboard.press_key(physical, WinitLogicalKey::Character("q".into()));
+        assert!(jump.pressed(&keyboard, crate::ModifierMatch::Exact));
+        keyboard.release(physical);
+        assert!(!keyboard.character_down("q"));
+    }
+
+    #[test]
+    fn key_names_round_trip() {
+        assert_eq!(TEST_KEY.name(), "H");
+        assert_eq!(WinitKey::from_name("h"), Some(TEST_KEY));
+        assert_eq!(WinitKey::from_name("Esc"), WinitKey::from_name("Escape"));
+        assert_eq!(WinitKey::from_name("Return"), WinitKey::from_name("Enter"));
+        assert_eq!(WinitKey::from_name("NotAKey"), None);
+
+        let shortcut: crate::Shortcut<WinitKey> = "Ctrl+H".parse().unwrap();
+        assert_eq!(shortcut.key, TEST_KEY);
+    }
+
+    #[test]
+    fn bindings_parse_winit_names() {
+        let bindings: WinitBindings = "jump = Space, MouseLeft\nquit = Ctrl+H\n".parse().unwrap();
+        assert_eq!(
+            bindings.bindings("jump")[1],
+            WinitBinding::Button(WinitMouseButton::Left)
+        );
+        assert_eq!(
+            bindings.to_string(),
+            "jump = Space, MouseLeft\nquit = Ctrl+H\n"
+        );
+    }
+
+    #[test]
+    fn mouse_button_names_round_trip() {
+        for button in [WinitMouseButton::Left, WinitMouseButton::Other(7)] {
+            assert_eq!(WinitMouseButton::from_name(&button.name()), Some(button));
+        }
+        assert_eq!(WinitMouseButton::Other(7).name(), "Mouse7");
+    }
+
     #[test]
     fn touch_via_event() {
         let mut touch = touch();
@@ -503,4 +1735,142 @@ mod tests {
 
         assert_eq!(touch.first_touch(), None);
     }
+
+    #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+    fn window_event(event: WindowEvent) -> WinitEvent<()> {
+        #[allow(unused_unsafe)]
+        let window_id = unsafe { winit::window::WindowId::dummy() };
+        WinitEvent::WindowEvent { window_id, event }
+    }
+
+    #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+    fn window_event(event: WindowEvent<'static>) -> WinitEvent<'static, ()> {
+        let window_id = unsafe { winit::window::WindowId::dummy() };
+        WinitEvent::WindowEvent { window_id, event }
+    }
+
+    fn dummy_device_id() -> WinitDeviceId {
+        #[allow(unused_unsafe)]
+        unsafe {
+            WinitDeviceId::dummy()
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "winit_0_21"))]
+    fn device_router_sends_modifiers_to_the_focused_keyboard() {
+        #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+        let ctrl = WinitMods::from(winit::keyboard::ModifiersState::CONTROL);
+        #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+        let ctrl = WinitMods::CTRL;
+        let modifiers = window_event(WindowEvent::ModifiersChanged(ctrl));
+        let device_id = dummy_device_id();
+
+        let mut router = device_keyboards();
+        router.handle_event(&modifiers);
+        assert_eq!(router.merged().modifiers(), Some(&ctrl));
+        assert_eq!(router.iter().count(), 0);
+
+        #[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+        router.set_focus(device_id).route(device_id);
+        #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+        router.handle_event(&make_keyboard_event(true, TEST_KEY));
+        assert_eq!(router.focused(), Some(&device_id));
+
+        router.handle_event(&modifiers);
+        assert_eq!(router.get(&device_id).unwrap().modifiers(), Some(&ctrl));
+    }
+
+    #[test]
+    fn removed_devices_are_disconnected() {
+        let device_id = dummy_device_id();
+        let removed: WinitEvent<()> = WinitEvent::DeviceEvent {
+            device_id,
+            event: DeviceEvent::Removed,
+        };
+
+        let mut slots = WinitPlayerSlots::new(2);
+        slots.join(InputSource::Keyboard(device_id));
+        slots.clear_events();
+        slots.handle_event(&removed);
+        assert!(slots.joined(0));
+        assert!(slots.sources(0).is_empty());
+        assert_eq!(slots.events().count(), 1);
+
+        let mut mice = device_mice();
+        mice.route(device_id);
+        mice.handle_event(&removed);
+        assert!(mice.get(&device_id).is_none());
+    }
+
+    #[test]
+    fn raw_motion_reaches_known_mice_and_the_focused_window() {
+        let device_id = dummy_device_id();
+        let motion: WinitEvent<()> = WinitEvent::DeviceEvent {
+            device_id,
+            event: DeviceEvent::MouseMotion { delta: (2.0, 3.0) },
+        };
+
+        let mut mice = device_mice();
+        mice.handle_event(&motion);
+        assert_eq!(mice.merged().motion_delta(), [2.0, 3.0]);
+        assert!(mice.get(&device_id).is_none());
+        mice.route(device_id);
+        mice.handle_event(&motion);
+        assert_eq!(mice.get(&device_id).unwrap().motion_delta(), [2.0, 3.0]);
+
+        let mut windows = windows();
+        let focused = window_event(WindowEvent::Focused(true));
+        windows.handle_event(&focused);
+        let window_id = *windows.focused().unwrap();
+        windows.handle_event(&motion);
+        assert_eq!(windows.merged().mouse.motion_delta(), [2.0, 3.0]);
+        assert_eq!(
+            windows.get(&window_id).unwrap().mouse.motion_delta(),
+            [2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn device_routers_only_route_their_kind_of_device() {
+        let device_id = dummy_device_id();
+        let click = make_mouse_button_event(true, WinitMouseButton::Left);
+
+        let mut keyboards = device_keyboards();
+        keyboards.handle_event(&click);
+        assert_eq!(keyboards.iter().count(), 0);
+        assert_eq!(keyboards.focused(), None);
+
+        let mut mice = device_mice();
+        mice.handle_event(&click);
+        assert!(mice.get(&device_id).unwrap().down(&WinitMouseButton::Left));
+
+        #[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+        {
+            let mut mice = device_mice();
+            mice.handle_event(&make_keyboard_event(true, TEST_KEY));
+            assert_eq!(mice.iter().count(), 0);
+            assert_eq!(mice.focused(), None);
+        }
+    }
+
+    #[test]
+    fn removed_devices_release_their_held_inputs() {
+        let device_id = dummy_device_id();
+        let removed: WinitEvent<()> = WinitEvent::DeviceEvent {
+            device_id,
+            event: DeviceEvent::Removed,
+        };
+
+        let mut mice = device_mice();
+        mice.handle_event(&make_mouse_button_event(true, WinitMouseButton::Left));
+        mice.handle_event(&removed);
+        assert!(mice.get(&device_id).is_none());
+        assert!(!mice.merged().down(&WinitMouseButton::Left));
+
+        let mut touches = Router::<WinitDeviceId, WinitTouchpad>::new();
+        touches.handle_event(&make_touch_event(1, [10.0, 10.0], TP::Started));
+        touches.handle_event(&removed);
+        assert!(touches.merged().touches().all(|t| t.released));
+    }
 }
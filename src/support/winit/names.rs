@@ -0,0 +1,478 @@
+//! Canonical names for `winit` keys and mouse buttons.
+//!
+//! Names are shared between winit versions where the keys are equivalent, so
+//! a binding written as `LCtrl` or `Backspace` means the same key whether the
+//! crate is built against winit 0.24 or 0.30. The variant names of every
+//! supported version are also accepted as aliases.
+
+use super::WinitKey;
+
+/// Alternative names accepted when parsing, mapped to canonical names.
+pub(super) const ALIASES: &[(&str, &str)] = &[
+    ("Digit0", "0"),
+    ("Key0", "0"),
+    ("Digit1", "1"),
+    ("Key1", "1"),
+    ("Digit2", "2"),
+    ("Key2", "2"),
+    ("Digit3", "3"),
+    ("Key3", "3"),
+    ("Digit4", "4"),
+    ("Key4", "4"),
+    ("Digit5", "5"),
+    ("Key5", "5"),
+    ("Digit6", "6"),
+    ("Key6", "6"),
+    ("Digit7", "7"),
+    ("Key7", "7"),
+    ("Digit8", "8"),
+    ("Key8", "8"),
+    ("Digit9", "9"),
+    ("Key9", "9"),
+    ("KeyA", "A"),
+    ("Quote", "Apostrophe"),
+    ("KeyB", "B"),
+    ("Back", "Backspace"),
+    ("WebBack", "BrowserBack"),
+    ("WebFavorites", "BrowserFavorites"),
+    ("WebForward", "BrowserForward"),
+    ("WebHome", "BrowserHome"),
+    ("WebRefresh", "BrowserRefresh"),
+    ("WebSearch", "BrowserSearch"),
+    ("WebStop", "BrowserStop"),
+    ("KeyC", "C"),
+    ("Capital", "CapsLock"),
+    ("KeyD", "D"),
+    ("Del", "Delete"),
+    ("ArrowDown", "Down"),
+    ("KeyE", "E"),
+    ("Return", "Enter"),
+    ("Equal", "Equals"),
+    ("Esc", "Escape"),
+    ("KeyF", "F"),
+    ("KeyG", "G"),
+    ("Backquote", "Grave"),
+    ("KeyH", "H"),
+    ("KeyI", "I"),
+    ("Ins", "Insert"),
+    ("OEM102", "IntlBackslash"),
+    ("KeyJ", "J"),
+    ("KeyK", "K"),
+    ("KanaMode", "Kana"),
+    ("KeyL", "L"),
+    ("AltLeft", "LAlt"),
+    ("BracketLeft", "LBracket"),
+    ("ControlLeft", "LCtrl"),
+    ("LControl", "LCtrl"),
+    ("ShiftLeft", "LShift"),
+    ("LWin", "LSuper"),
+    ("SuperLeft", "LSuper"),
+    ("ArrowLeft", "Left"),
+    ("KeyM", "M"),
+    ("LaunchMail", "Mail"),
+    ("Apps", "Menu"),
+    ("ContextMenu", "Menu"),
+    ("AudioVolumeMute", "Mute"),
+    ("KeyN", "N"),
+    ("MediaTrackNext", "NextTrack"),
+    ("NoConvert", "NonConvert"),
+    ("NumpadEqual", "NumpadEquals"),
+    ("KeyO", "O"),
+    ("KeyP", "P"),
+    ("PgDn", "PageDown"),
+    ("PgUp", "PageUp"),
+    ("MediaPlayPause", "PlayPause"),
+    ("MediaTrackPrevious", "PrevTrack"),
+    ("Snapshot", "PrintScreen"),
+    ("KeyQ", "Q"),
+    ("KeyR", "R"),
+    ("AltRight", "RAlt"),
+    ("BracketRight", "RBracket"),
+    ("ControlRight", "RCtrl"),
+    ("RControl", "RCtrl"),
+    ("ShiftRight", "RShift"),
+    ("RWin", "RSuper"),
+    ("SuperRight", "RSuper"),
+    ("ArrowRight", "Right"),
+    ("KeyS", "S"),
+    ("Scroll", "ScrollLock"),
+    ("KeyT", "T"),
+    ("KeyU", "U"),
+    ("ArrowUp", "Up"),
+    ("KeyV", "V"),
+    ("AudioVolumeDown", "VolumeDown"),
+    ("AudioVolumeUp", "VolumeUp"),
+    ("KeyW", "W"),
+    ("WakeUp", "Wake"),
+    ("KeyX", "X"),
+    ("KeyY", "Y"),
+    ("IntlYen", "Yen"),
+    ("KeyZ", "Z"),
+];
+
+/// Canonical names for each key code.
+#[cfg(any(feature = "winit_0_29", feature = "winit_0_30"))]
+pub(super) const KEYS: &[(WinitKey, &str)] = &[
+    (WinitKey::Backquote, "Grave"),
+    (WinitKey::Backslash, "Backslash"),
+    (WinitKey::BracketLeft, "LBracket"),
+    (WinitKey::BracketRight, "RBracket"),
+    (WinitKey::Comma, "Comma"),
+    (WinitKey::Digit0, "0"),
+    (WinitKey::Digit1, "1"),
+    (WinitKey::Digit2, "2"),
+    (WinitKey::Digit3, "3"),
+    (WinitKey::Digit4, "4"),
+    (WinitKey::Digit5, "5"),
+    (WinitKey::Digit6, "6"),
+    (WinitKey::Digit7, "7"),
+    (WinitKey::Digit8, "8"),
+    (WinitKey::Digit9, "9"),
+    (WinitKey::Equal, "Equals"),
+    (WinitKey::IntlBackslash, "IntlBackslash"),
+    (WinitKey::IntlRo, "IntlRo"),
+    (WinitKey::IntlYen, "Yen"),
+    (WinitKey::KeyA, "A"),
+    (WinitKey::KeyB, "B"),
+    (WinitKey::KeyC, "C"),
+    (WinitKey::KeyD, "D"),
+    (WinitKey::KeyE, "E"),
+    (WinitKey::KeyF, "F"),
+    (WinitKey::KeyG, "G"),
+    (WinitKey::KeyH, "H"),
+    (WinitKey::KeyI, "I"),
+    (WinitKey::KeyJ, "J"),
+    (WinitKey::KeyK, "K"),
+    (WinitKey::KeyL, "L"),
+    (WinitKey::KeyM, "M"),
+    (WinitKey::KeyN, "N"),
+    (WinitKey::KeyO, "O"),
+    (WinitKey::KeyP, "P"),
+    (WinitKey::KeyQ, "Q"),
+    (WinitKey::KeyR, "R"),
+    (WinitKey::KeyS, "S"),
+    (WinitKey::KeyT, "T"),
+    (WinitKey::KeyU, "U"),
+    (WinitKey::KeyV, "V"),
+    (WinitKey::KeyW, "W"),
+    (WinitKey::KeyX, "X"),
+    (WinitKey::KeyY, "Y"),
+    (WinitKey::KeyZ, "Z"),
+    (WinitKey::Minus, "Minus"),
+    (WinitKey::Period, "Period"),
+    (WinitKey::Quote, "Apostrophe"),
+    (WinitKey::Semicolon, "Semicolon"),
+    (WinitKey::Slash, "Slash"),
+    (WinitKey::AltLeft, "LAlt"),
+    (WinitKey::AltRight, "RAlt"),
+    (WinitKey::Backspace, "Backspace"),
+    (WinitKey::CapsLock, "CapsLock"),
+    (WinitKey::ContextMenu, "Menu"),
+    (WinitKey::ControlLeft, "LCtrl"),
+    (WinitKey::ControlRight, "RCtrl"),
+    (WinitKey::Enter, "Enter"),
+    (WinitKey::SuperLeft, "LSuper"),
+    (WinitKey::SuperRight, "RSuper"),
+    (WinitKey::ShiftLeft, "LShift"),
+    (WinitKey::ShiftRight, "RShift"),
+    (WinitKey::Space, "Space"),
+    (WinitKey::Tab, "Tab"),
+    (WinitKey::Convert, "Convert"),
+    (WinitKey::KanaMode, "Kana"),
+    (WinitKey::Lang1, "Lang1"),
+    (WinitKey::Lang2, "Lang2"),
+    (WinitKey::Lang3, "Lang3"),
+    (WinitKey::Lang4, "Lang4"),
+    (WinitKey::Lang5, "Lang5"),
+    (WinitKey::NonConvert, "NonConvert"),
+    (WinitKey::Delete, "Delete"),
+    (WinitKey::End, "End"),
+    (WinitKey::Help, "Help"),
+    (WinitKey::Home, "Home"),
+    (WinitKey::Insert, "Insert"),
+    (WinitKey::PageDown, "PageDown"),
+    (WinitKey::PageUp, "PageUp"),
+    (WinitKey::ArrowDown, "Down"),
+    (WinitKey::ArrowLeft, "Left"),
+    (WinitKey::ArrowRight, "Right"),
+    (WinitKey::ArrowUp, "Up"),
+    (WinitKey::NumLock, "NumLock"),
+    (WinitKey::Numpad0, "Numpad0"),
+    (WinitKey::Numpad1, "Numpad1"),
+    (WinitKey::Numpad2, "Numpad2"),
+    (WinitKey::Numpad3, "Numpad3"),
+    (WinitKey::Numpad4, "Numpad4"),
+    (WinitKey::Numpad5, "Numpad5"),
+    (WinitKey::Numpad6, "Numpad6"),
+    (WinitKey::Numpad7, "Numpad7"),
+    (WinitKey::Numpad8, "Numpad8"),
+    (WinitKey::Numpad9, "Numpad9"),
+    (WinitKey::NumpadAdd, "NumpadAdd"),
+    (WinitKey::NumpadBackspace, "NumpadBackspace"),
+    (WinitKey::NumpadClear, "NumpadClear"),
+    (WinitKey::NumpadClearEntry, "NumpadClearEntry"),
+    (WinitKey::NumpadComma, "NumpadComma"),
+    (WinitKey::NumpadDecimal, "NumpadDecimal"),
+    (WinitKey::NumpadDivide, "NumpadDivide"),
+    (WinitKey::NumpadEnter, "NumpadEnter"),
+    (WinitKey::NumpadEqual, "NumpadEquals"),
+    (WinitKey::NumpadHash, "NumpadHash"),
+    (WinitKey::NumpadMemoryAdd, "NumpadMemoryAdd"),
+    (WinitKey::NumpadMemoryClear, "NumpadMemoryClear"),
+    (WinitKey::NumpadMemoryRecall, "NumpadMemoryRecall"),
+    (WinitKey::NumpadMemoryStore, "NumpadMemoryStore"),
+    (WinitKey::NumpadMemorySubtract, "NumpadMemorySubtract"),
+    (WinitKey::NumpadMultiply, "NumpadMultiply"),
+    (WinitKey::NumpadParenLeft, "NumpadParenLeft"),
+    (WinitKey::NumpadParenRight, "NumpadParenRight"),
+    (WinitKey::NumpadStar, "NumpadStar"),
+    (WinitKey::NumpadSubtract, "NumpadSubtract"),
+    (WinitKey::Escape, "Escape"),
+    (WinitKey::Fn, "Fn"),
+    (WinitKey::FnLock, "FnLock"),
+    (WinitKey::PrintScreen, "PrintScreen"),
+    (WinitKey::ScrollLock, "ScrollLock"),
+    (WinitKey::Pause, "Pause"),
+    (WinitKey::BrowserBack, "BrowserBack"),
+    (WinitKey::BrowserFavorites, "BrowserFavorites"),
+    (WinitKey::BrowserForward, "BrowserForward"),
+    (WinitKey::BrowserHome, "BrowserHome"),
+    (WinitKey::BrowserRefresh, "BrowserRefresh"),
+    (WinitKey::BrowserSearch, "BrowserSearch"),
+    (WinitKey::BrowserStop, "BrowserStop"),
+    (WinitKey::Eject, "Eject"),
+    (WinitKey::LaunchApp1, "LaunchApp1"),
+    (WinitKey::LaunchApp2, "LaunchApp2"),
+    (WinitKey::LaunchMail, "Mail"),
+    (WinitKey::MediaPlayPause, "PlayPause"),
+    (WinitKey::MediaSelect, "MediaSelect"),
+    (WinitKey::MediaStop, "MediaStop"),
+    (WinitKey::MediaTrackNext, "NextTrack"),
+    (WinitKey::MediaTrackPrevious, "PrevTrack"),
+    (WinitKey::Power, "Power"),
+    (WinitKey::Sleep, "Sleep"),
+    (WinitKey::AudioVolumeDown, "VolumeDown"),
+    (WinitKey::AudioVolumeMute, "Mute"),
+    (WinitKey::AudioVolumeUp, "VolumeUp"),
+    (WinitKey::WakeUp, "Wake"),
+    (WinitKey::Meta, "Meta"),
+    (WinitKey::Hyper, "Hyper"),
+    (WinitKey::Turbo, "Turbo"),
+    (WinitKey::Abort, "Abort"),
+    (WinitKey::Resume, "Resume"),
+    (WinitKey::Suspend, "Suspend"),
+    (WinitKey::Again, "Again"),
+    (WinitKey::Copy, "Copy"),
+    (WinitKey::Cut, "Cut"),
+    (WinitKey::Find, "Find"),
+    (WinitKey::Open, "Open"),
+    (WinitKey::Paste, "Paste"),
+    (WinitKey::Props, "Props"),
+    (WinitKey::Select, "Select"),
+    (WinitKey::Undo, "Undo"),
+    (WinitKey::Hiragana, "Hiragana"),
+    (WinitKey::Katakana, "Katakana"),
+    (WinitKey::F1, "F1"),
+    (WinitKey::F2, "F2"),
+    (WinitKey::F3, "F3"),
+    (WinitKey::F4, "F4"),
+    (WinitKey::F5, "F5"),
+    (WinitKey::F6, "F6"),
+    (WinitKey::F7, "F7"),
+    (WinitKey::F8, "F8"),
+    (WinitKey::F9, "F9"),
+    (WinitKey::F10, "F10"),
+    (WinitKey::F11, "F11"),
+    (WinitKey::F12, "F12"),
+    (WinitKey::F13, "F13"),
+    (WinitKey::F14, "F14"),
+    (WinitKey::F15, "F15"),
+    (WinitKey::F16, "F16"),
+    (WinitKey::F17, "F17"),
+    (WinitKey::F18, "F18"),
+    (WinitKey::F19, "F19"),
+    (WinitKey::F20, "F20"),
+    (WinitKey::F21, "F21"),
+    (WinitKey::F22, "F22"),
+    (WinitKey::F23, "F23"),
+    (WinitKey::F24, "F24"),
+    (WinitKey::F25, "F25"),
+    (WinitKey::F26, "F26"),
+    (WinitKey::F27, "F27"),
+    (WinitKey::F28, "F28"),
+    (WinitKey::F29, "F29"),
+    (WinitKey::F30, "F30"),
+    (WinitKey::F31, "F31"),
+    (WinitKey::F32, "F32"),
+    (WinitKey::F33, "F33"),
+    (WinitKey::F34, "F34"),
+    (WinitKey::F35, "F35"),
+];
+
+/// Canonical names for each virtual key code.
+#[cfg(not(any(feature = "winit_0_29", feature = "winit_0_30")))]
+pub(super) const KEYS: &[(WinitKey, &str)] = &[
+    (WinitKey::Key1, "1"),
+    (WinitKey::Key2, "2"),
+    (WinitKey::Key3, "3"),
+    (WinitKey::Key4, "4"),
+    (WinitKey::Key5, "5"),
+    (WinitKey::Key6, "6"),
+    (WinitKey::Key7, "7"),
+    (WinitKey::Key8, "8"),
+    (WinitKey::Key9, "9"),
+    (WinitKey::Key0, "0"),
+    (WinitKey::A, "A"),
+    (WinitKey::B, "B"),
+    (WinitKey::C, "C"),
+    (WinitKey::D, "D"),
+    (WinitKey::E, "E"),
+    (WinitKey::F, "F"),
+    (WinitKey::G, "G"),
+    (WinitKey::H, "H"),
+    (WinitKey::I, "I"),
+    (WinitKey::J, "J"),
+    (WinitKey::K, "K"),
+    (WinitKey::L, "L"),
+    (WinitKey::M, "M"),
+    (WinitKey::N, "N"),
+    (WinitKey::O, "O"),
+    (WinitKey::P, "P"),
+    (WinitKey::Q, "Q"),
+    (WinitKey::R, "R"),
+    (WinitKey::S, "S"),
+    (WinitKey::T, "T"),
+    (WinitKey::U, "U"),
+    (WinitKey::V, "V"),
+    (WinitKey::W, "W"),
+    (WinitKey::X, "X"),
+    (WinitKey::Y, "Y"),
+    (WinitKey::Z, "Z"),
+    (WinitKey::Escape, "Escape"),
+    (WinitKey::F1, "F1"),
+    (WinitKey::F2, "F2"),
+    (WinitKey::F3, "F3"),
+    (WinitKey::F4, "F4"),
+    (WinitKey::F5, "F5"),
+    (WinitKey::F6, "F6"),
+    (WinitKey::F7, "F7"),
+    (WinitKey::F8, "F8"),
+    (WinitKey::F9, "F9"),
+    (WinitKey::F10, "F10"),
+    (WinitKey::F11, "F11"),
+    (WinitKey::F12, "F12"),
+    (WinitKey::F13, "F13"),
+    (WinitKey::F14, "F14"),
+    (WinitKey::F15, "F15"),
+    (WinitKey::F16, "F16"),
+    (WinitKey::F17, "F17"),
+    (WinitKey::F18, "F18"),
+    (WinitKey::F19, "F19"),
+    (WinitKey::F20, "F20"),
+    (WinitKey::F21, "F21"),
+    (WinitKey::F22, "F22"),
+    (WinitKey::F23, "F23"),
+    (WinitKey::F24, "F24"),
+    (WinitKey::Snapshot, "PrintScreen"),
+    (WinitKey::Scroll, "ScrollLock"),
+    (WinitKey::Pause, "Pause"),
+    (WinitKey::Insert, "Insert"),
+    (WinitKey::Home, "Home"),
+    (WinitKey::Delete, "Delete"),
+    (WinitKey::End, "End"),
+    (WinitKey::PageDown, "PageDown"),
+    (WinitKey::PageUp, "PageUp"),
+    (WinitKey::Left, "Left"),
+    (WinitKey::Up, "Up"),
+    (WinitKey::Right, "Right"),
+    (WinitKey::Down, "Down"),
+    (WinitKey::Back, "Backspace"),
+    (WinitKey::Return, "Enter"),
+    (WinitKey::Space, "Space"),
+    (WinitKey::Compose, "Compose"),
+    (WinitKey::Caret, "Caret"),
+    (WinitKey::Numlock, "NumLock"),
+    (WinitKey::Numpad0, "Numpad0"),
+    (WinitKey::Numpad1, "Numpad1"),
+    (WinitKey::Numpad2, "Numpad2"),
+    (WinitKey::Numpad3, "Numpad3"),
+    (WinitKey::Numpad4, "Numpad4"),
+    (WinitKey::Numpad5, "Numpad5"),
+    (WinitKey::Numpad6, "Numpad6"),
+    (WinitKey::Numpad7, "Numpad7"),
+    (WinitKey::Numpad8, "Numpad8"),
+    (WinitKey::Numpad9, "Numpad9"),
+    (WinitKey::NumpadAdd, "NumpadAdd"),
+    (WinitKey::NumpadDivide, "NumpadDivide"),
+    (WinitKey::NumpadDecimal, "NumpadDecimal"),
+    (WinitKey::NumpadComma, "NumpadComma"),
+    (WinitKey::NumpadEnter, "NumpadEnter"),
+    (WinitKey::NumpadEquals, "NumpadEquals"),
+    (WinitKey::NumpadMultiply, "NumpadMultiply"),
+    (WinitKey::NumpadSubtract, "NumpadSubtract"),
+    (WinitKey::AbntC1, "AbntC1"),
+    (WinitKey::AbntC2, "AbntC2"),
+    (WinitKey::Apostrophe, "Apostrophe"),
+    (WinitKey::Apps, "Menu"),
+    (WinitKey::Asterisk, "Asterisk"),
+    (WinitKey::At, "At"),
+    (WinitKey::Ax, "Ax"),
+    (WinitKey::Backslash, "Backslash"),
+    (WinitKey::Calculator, "Calculator"),
+    (WinitKey::Capital, "CapsLock"),
+    (WinitKey::Colon, "Colon"),
+    (WinitKey::Comma, "Comma"),
+    (WinitKey::Convert, "Convert"),
+    (WinitKey::Equals, "Equals"),
+    (WinitKey::Grave, "Grave"),
+    (WinitKey::Kana, "Kana"),
+    (WinitKey::Kanji, "Kanji"),
+    (WinitKey::LAlt, "LAlt"),
+    (WinitKey::LBracket, "LBracket"),
+    (WinitKey::LControl, "LCtrl"),
+    (WinitKey::LShift, "LShift"),
+    (WinitKey::LWin, "LSuper"),
+    (WinitKey::Mail, "Mail"),
+    (WinitKey::MediaSelect, "MediaSelect"),
+    (WinitKey::MediaStop, "MediaStop"),
+    (WinitKey::Minus, "Minus"),
+    (WinitKey::Mute, "Mute"),
+    (WinitKey::MyComputer, "MyComputer"),
+    (WinitKey::NavigateForward, "NavigateForward"),
+    (WinitKey::NavigateBackward, "NavigateBackward"),
+    (WinitKey::NextTrack, "NextTrack"),
+    (WinitKey::NoConvert, "NonConvert"),
+    (WinitKey::OEM102, "IntlBackslash"),
+    (WinitKey::Period, "Period"),
+    (WinitKey::PlayPause, "PlayPause"),
+    (WinitKey::Plus, "Plus"),
+    (WinitKey::Power, "Power"),
+    (WinitKey::PrevTrack, "PrevTrack"),
+    (WinitKey::RAlt, "RAlt"),
+    (WinitKey::RBracket, "RBracket"),
+    (WinitKey::RControl, "RCtrl"),
+    (WinitKey::RShift, "RShift"),
+    (WinitKey::RWin, "RSuper"),
+    (WinitKey::Semicolon, "Semicolon"),
+    (WinitKey::Slash, "Slash"),
+    (WinitKey::Sleep, "Sleep"),
+    (WinitKey::Stop, "Stop"),
+    (WinitKey::Sysrq, "Sysrq"),
+    (WinitKey::Tab, "Tab"),
+    (WinitKey::Underline, "Underline"),
+    (WinitKey::Unlabeled, "Unlabeled"),
+    (WinitKey::VolumeDown, "VolumeDown"),
+    (WinitKey::VolumeUp, "VolumeUp"),
+    (WinitKey::Wake, "Wake"),
+    (WinitKey::WebBack, "BrowserBack"),
+    (WinitKey::WebFavorites, "BrowserFavorites"),
+    (WinitKey::WebForward, "BrowserForward"),
+    (WinitKey::WebHome, "BrowserHome"),
+    (WinitKey::WebRefresh, "BrowserRefresh"),
+    (WinitKey::WebSearch, "BrowserSearch"),
+    (WinitKey::WebStop, "BrowserStop"),
+    (WinitKey::Yen, "Yen"),
+    (WinitKey::Copy, "Copy"),
+    (WinitKey::Paste, "Paste"),
+    (WinitKey::Cut, "Cut"),
+];
@@ -0,0 +1,219 @@
+use crate::keyboard::Modifiers;
+use crate::shortcut::Platform;
+
+/// A semantic text editing command, such as deleting the previous character
+/// or pasting from the clipboard.
+///
+/// Commands are decoded from key presses, so text widgets don't need to know
+/// each platform's editing shortcuts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextCommand {
+    /// Delete the character before the cursor (Backspace).
+    DeleteBackward,
+
+    /// Delete the character after the cursor (Delete).
+    DeleteForward,
+
+    /// Delete the word before the cursor.
+    DeleteWordBackward,
+
+    /// Delete the word after the cursor.
+    DeleteWordForward,
+
+    /// Move the cursor, clearing any selection.
+    Move(TextMotion),
+
+    /// Move the cursor, extending the selection.
+    Select(TextMotion),
+
+    SelectAll,
+    Copy,
+    Cut,
+    Paste,
+    Undo,
+    Redo,
+
+    /// Insert a line break (Enter).
+    Newline,
+
+    /// Insert a tab, or move focus (Tab).
+    Tab,
+}
+
+/// A direction the cursor can move in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextMotion {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    DocumentStart,
+    DocumentEnd,
+}
+
+/// The keys that take part in text editing shortcuts, independent of any
+/// windowing library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditKey {
+    Backspace,
+    Delete,
+    Insert,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Enter,
+    Tab,
+    A,
+    C,
+    V,
+    X,
+    Y,
+    Z,
+}
+
+impl TextCommand {
+    /// Returns the command triggered by pressing `key` while `modifiers` are
+    /// held, following the editing conventions of `platform`.
+    ///
+    /// Enter is a `Newline` whatever modifiers are held, since line breaks
+    /// are left out of a keyboard's `text`. Widgets that give a chord such as
+    /// Ctrl+Enter another meaning can check the modifiers themselves.
+    pub fn for_key(key: EditKey, modifiers: Modifiers, platform: Platform) -> Option<Self> {
+        use EditKey::*;
+
+        let mac = platform == Platform::MacOs;
+        let Modifiers {
+            ctrl,
+            alt,
+            shift,
+            logo,
+        } = modifiers;
+
+        // Primary is the shortcut modifier (Cmd on macOS, Ctrl elsewhere),
+        // and word is the modifier that moves by words (Alt on macOS).
+        let (primary, word) = match mac {
+            true => (logo && !ctrl && !alt, alt && !ctrl && !logo),
+            false => (ctrl && !alt && !logo, ctrl && !alt && !logo),
+        };
+        let plain = !ctrl && !alt && !logo;
+
+        let motion = |motion: TextMotion| match shift {
+            true => Some(TextCommand::Select(motion)),
+            false => Some(TextCommand::Move(motion)),
+        };
+
+        match key {
+            Backspace if plain => Some(TextCommand::DeleteBackward),
+            Backspace if word && !shift => Some(TextCommand::DeleteWordBackward),
+            Delete if plain && shift && !mac => Some(TextCommand::Cut),
+            Delete if plain => Some(TextCommand::DeleteForward),
+            Delete if word && !shift => Some(TextCommand::DeleteWordForward),
+            Insert if plain && shift && !mac => Some(TextCommand::Paste),
+            Insert if primary && !shift && !mac => Some(TextCommand::Copy),
+            Left if plain => motion(TextMotion::Left),
+            Right if plain => motion(TextMotion::Right),
+            Left if word => motion(TextMotion::WordLeft),
+            Right if word => motion(TextMotion::WordRight),
+            Left if primary && mac => motion(TextMotion::LineStart),
+            Right if primary && mac => motion(TextMotion::LineEnd),
+            Up if plain => motion(TextMotion::Up),
+            Down if plain => motion(TextMotion::Down),
+            Up if primary && mac => motion(TextMotion::DocumentStart),
+            Down if primary && mac => motion(TextMotion::DocumentEnd),
+            Home if plain && mac => motion(TextMotion::DocumentStart),
+            End if plain && mac => motion(TextMotion::DocumentEnd),
+            Home if plain => motion(TextMotion::LineStart),
+            End if plain => motion(TextMotion::LineEnd),
+            Home if primary && !mac => motion(TextMotion::DocumentStart),
+            End if primary && !mac => motion(TextMotion::DocumentEnd),
+            Enter => Some(TextCommand::Newline),
+            Tab if plain && !shift => Some(TextCommand::Tab),
+            A if primary && !shift => Some(TextCommand::SelectAll),
+            C if primary && !shift => Some(TextCommand::Copy),
+            V if primary && !shift => Some(TextCommand::Paste),
+            X if primary && !shift => Some(TextCommand::Cut),
+            Z if primary && shift => Some(TextCommand::Redo),
+            Z if primary => Some(TextCommand::Undo),
+            Y if primary && !shift && !mac => Some(TextCommand::Redo),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mods(ctrl: bool, alt: bool, shift: bool, logo: bool) -> Modifiers {
+        Modifiers {
+            ctrl,
+            alt,
+            shift,
+            logo,
+        }
+    }
+
+    #[test]
+    fn word_and_shortcut_modifiers_follow_platform() {
+        let ctrl = mods(true, false, false, false);
+        let alt = mods(false, true, false, false);
+        let cmd = mods(false, false, false, true);
+
+        let linux = Platform::Linux;
+        let mac = Platform::MacOs;
+        assert_eq!(
+            TextCommand::for_key(EditKey::Backspace, ctrl, linux),
+            Some(TextCommand::DeleteWordBackward)
+        );
+        assert_eq!(
+            TextCommand::for_key(EditKey::Backspace, alt, mac),
+            Some(TextCommand::DeleteWordBackward)
+        );
+        assert_eq!(
+            TextCommand::for_key(EditKey::V, ctrl, linux),
+            Some(TextCommand::Paste)
+        );
+        assert_eq!(TextCommand::for_key(EditKey::V, ctrl, mac), None);
+        assert_eq!(
+            TextCommand::for_key(EditKey::V, cmd, mac),
+            Some(TextCommand::Paste)
+        );
+    }
+
+    #[test]
+    fn enter_is_a_newline_with_any_modifiers() {
+        let shift = mods(false, false, true, false);
+        let ctrl = mods(true, false, false, false);
+        for modifiers in [Modifiers::default(), shift, ctrl] {
+            assert_eq!(
+                TextCommand::for_key(EditKey::Enter, modifiers, Platform::Linux),
+                Some(TextCommand::Newline)
+            );
+        }
+    }
+
+    #[test]
+    fn shift_extends_selection() {
+        let shift = mods(false, false, true, false);
+        let ctrl_shift = mods(true, false, true, false);
+        assert_eq!(
+            TextCommand::for_key(EditKey::Left, shift, Platform::Windows),
+            Some(TextCommand::Select(TextMotion::Left))
+        );
+        assert_eq!(
+            TextCommand::for_key(EditKey::Right, ctrl_shift, Platform::Windows),
+            Some(TextCommand::Select(TextMotion::WordRight))
+        );
+        assert_eq!(
+            TextCommand::for_key(EditKey::Z, ctrl_shift, Platform::Windows),
+            Some(TextCommand::Redo)
+        );
+    }
+}
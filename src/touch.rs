@@ -1,4 +1,6 @@
 use crate::Event;
+use crate::events::{EventLog, TouchEvent};
+use crate::transform::{Transform, TransformedTouchpad};
 use std::ops::Add;
 
 /// Represents an active touch on the touch device.
@@ -23,8 +25,9 @@ pub enum TouchPhase {
     Move,
 }
 
-/// A trait for objects that can represent the state of a touch device.
-pub trait TouchInterface {
+/// A trait for reading the state of a touch device. This is implemented by
+/// every `TouchInterface`, and by read-only views such as `LayerTouchpad`.
+pub trait TouchQuery {
     /// A type that can uniquely represent a touch.
     type TouchId: PartialEq;
 
@@ -42,6 +45,28 @@ pub trait TouchInterface {
     /// (This includes touches that have been released this frame.)
     fn touches(&self) -> impl Iterator<Item = &Touch<Self::TouchId, Self::Coord>>;
 
+    /// Returns an iterator over this frame's touch events, in the order they
+    /// were received. This is empty unless event logging is enabled.
+    ///
+    /// The default implementation returns nothing.
+    fn events(&self) -> impl Iterator<Item = &TouchEvent<Self::TouchId, Self::Coord>> {
+        std::iter::empty()
+    }
+
+    /// Returns a view of this touchpad with touch positions converted by
+    /// `transform`.
+    fn transformed<T>(&self, transform: T) -> TransformedTouchpad<'_, Self, T>
+    where
+        Self: Sized,
+        Self::TouchId: Clone,
+        T: Transform<Self::Coord>,
+    {
+        TransformedTouchpad::new(self, transform)
+    }
+}
+
+/// A trait for objects that can represent the state of a touch device.
+pub trait TouchInterface: TouchQuery {
     /// Register a touch event.
     fn touch_event<I, P>(&mut self, id: I, position: [Self::Coord; 2], phase: P) -> &mut Self
     where
@@ -51,6 +76,26 @@ pub trait TouchInterface {
     /// Clears the tapped/released state of active touches. Should be called at the end of each frame.
     fn clear_taps(&mut self) -> &mut Self;
 
+    /// Cancel every active touch, for example when the window loses focus
+    /// and the real touch ends will never arrive.
+    ///
+    /// The default implementation sends a `TouchPhase::Cancel` event for
+    /// each touch that hasn't been released.
+    fn release_all(&mut self) -> &mut Self
+    where
+        Self::TouchId: Clone,
+    {
+        let active: Vec<_> = self
+            .touches()
+            .filter(|t| !t.released)
+            .map(|t| (t.id.clone(), t.position))
+            .collect();
+        for (id, position) in active {
+            self.touch_event(id, position, TouchPhase::Cancel);
+        }
+        self
+    }
+
     /// Convenience method for handling events. The type of event, `E`, will
     /// vary depending on the windowing library being used.
     fn handle_event<E: Event<Self>>(&mut self, event: &E) -> &mut Self {
@@ -67,6 +112,8 @@ where
     Coord: Copy + Default + Add<Output = Coord>,
 {
     touches: Vec<Touch<Id, Coord>>,
+    scale_factor: f64,
+    events: EventLog<TouchEvent<Id, Coord>>,
 }
 
 impl<Id, Coord> Default for Touchpad<Id, Coord>
@@ -87,11 +134,95 @@ where
     pub fn new() -> Self {
         Touchpad {
             touches: Vec::with_capacity(4),
+            scale_factor: 1.0,
+            events: Default::default(),
         }
     }
 }
 
-impl<Id, C> TouchInterface for Touchpad<Id, C>
+impl<Id, Coord> Touchpad<Id, Coord>
+where
+    Id: Clone + PartialEq,
+    Coord: Copy + Default + Add<Output = Coord>,
+{
+    /// Enable or disable recording an ordered log of each frame's events,
+    /// available through `events`.
+    pub fn set_event_log(&mut self, enabled: bool) -> &mut Self {
+        self.events.set_enabled(enabled);
+        self
+    }
+
+    fn record(&mut self, index: usize, phase: TouchPhase) {
+        if self.events.enabled() {
+            let touch = &self.touches[index];
+            self.events.record(TouchEvent {
+                id: touch.id.clone(),
+                position: touch.position,
+                phase,
+            });
+        }
+    }
+}
+
+/// Conversion between physical pixels, which touch positions are measured
+/// in, and logical points, which are physical pixels divided by the scale
+/// factor.
+impl<Id> Touchpad<Id, f64>
+where
+    Id: PartialEq,
+{
+    /// Returns the ratio of physical pixels to logical points.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Set the ratio of physical pixels to logical points, such as from
+    /// the window's initial scale factor. Touch positions are left as they
+    /// are, since they are already in physical pixels. Factors that aren't
+    /// positive are ignored.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) -> &mut Self {
+        if scale_factor.is_finite() && scale_factor > 0.0 {
+            self.scale_factor = scale_factor;
+        }
+        self
+    }
+
+    /// Register that the ratio of physical pixels to logical points changed.
+    /// Active touches keep their logical positions, so their physical
+    /// positions are rescaled. Factors that aren't positive are ignored.
+    pub fn scale_factor_changed(&mut self, scale_factor: f64) -> &mut Self {
+        if !(scale_factor.is_finite() && scale_factor > 0.0) {
+            return self;
+        }
+        let ratio = scale_factor / self.scale_factor;
+        for touch in &mut self.touches {
+            let [x, y] = touch.position;
+            touch.position = [x * ratio, y * ratio];
+        }
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Returns the position of a touch in logical points, if it exists.
+    pub fn logical_position(&self, id: &Id) -> Option<[f64; 2]> {
+        self.touches
+            .iter()
+            .find(|t| &t.id == id)
+            .map(|t| self.to_logical(t.position))
+    }
+
+    /// Convert a position in physical pixels to logical points.
+    pub fn to_logical(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [x / self.scale_factor, y / self.scale_factor]
+    }
+
+    /// Convert a position in logical points to physical pixels.
+    pub fn to_physical(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [x * self.scale_factor, y * self.scale_factor]
+    }
+}
+
+impl<Id, C> TouchQuery for Touchpad<Id, C>
 where
     Id: PartialEq,
     C: Copy + Default + Add<Output = C>,
@@ -111,15 +242,25 @@ where
         self.touches.iter()
     }
 
+    fn events(&self) -> impl Iterator<Item = &TouchEvent<Self::TouchId, Self::Coord>> {
+        self.events.iter()
+    }
+}
+
+impl<Id, C> TouchInterface for Touchpad<Id, C>
+where
+    Id: Clone + PartialEq,
+    C: Copy + Default + Add<Output = C>,
+{
     fn touch_event<I, P>(&mut self, id: I, position: [Self::Coord; 2], phase: P) -> &mut Self
     where
         I: Into<Self::TouchId>,
         P: Into<TouchPhase>,
     {
         let id = id.into();
-        let existing_touch = self.touches.iter_mut().find(|t| t.id == id);
-        let existing_touch = match existing_touch {
-            Some(t) => t,
+        let phase = phase.into();
+        let index = match self.touches.iter().position(|t| t.id == id) {
+            Some(index) => index,
             None => {
                 self.touches.push(Touch {
                     id,
@@ -127,17 +268,29 @@ where
                     tapped: false,
                     released: false,
                 });
-                self.touches.last_mut().unwrap()
+                self.touches.len() - 1
             }
         };
 
-        match phase.into() {
+        let existing_touch = &mut self.touches[index];
+        match phase {
             TouchPhase::Start => existing_touch.tapped = true,
             TouchPhase::Cancel | TouchPhase::End => existing_touch.released = true,
             _ => (),
         }
 
         existing_touch.position = position;
+        self.record(index, phase);
+        self
+    }
+
+    fn release_all(&mut self) -> &mut Self {
+        for index in 0..self.touches.len() {
+            if !self.touches[index].released {
+                self.touches[index].released = true;
+                self.record(index, TouchPhase::Cancel);
+            }
+        }
         self
     }
 
@@ -146,6 +299,70 @@ where
             touch.tapped = false;
         }
         self.touches.retain(|t| !t.released);
+        self.events.clear();
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestTouchpad = Touchpad<u64, f64>;
+
+    #[test]
+    fn touch_release_all_cancels_active_touches() {
+        let mut touchpad = TestTouchpad::new();
+        touchpad
+            .touch_event(1u64, [0.0, 0.0], TouchPhase::Start)
+            .touch_event(2u64, [5.0, 5.0], TouchPhase::Start);
+        touchpad.clear_taps();
+        touchpad.set_event_log(true);
+        touchpad.release_all();
+        assert!(touchpad.touches().all(|t| t.released && !t.tapped));
+        assert!(touchpad.events().all(|e| e.phase == TouchPhase::Cancel));
+        assert_eq!(touchpad.events().count(), 2);
+
+        touchpad.clear_taps();
+        assert!(touchpad.first_touch().is_none());
+    }
+
+    #[test]
+    fn touch_logical_positions_follow_scale_factor() {
+        let mut touchpad = TestTouchpad::new();
+        touchpad
+            .set_scale_factor(2.0)
+            .touch_event(1u64, [100.0, 50.0], TouchPhase::Start);
+        assert_eq!(touchpad.logical_position(&1), Some([50.0, 25.0]));
+        assert_eq!(touchpad.logical_position(&2), None);
+
+        touchpad.scale_factor_changed(4.0);
+        assert_eq!(touchpad.logical_position(&1), Some([50.0, 25.0]));
+        assert_eq!(touchpad.first_touch().unwrap().position, [200.0, 100.0]);
+
+        touchpad.set_scale_factor(0.0).scale_factor_changed(-1.0);
+        assert_eq!(touchpad.scale_factor(), 4.0);
+    }
+
+    #[test]
+    fn touch_event_log_keeps_order() {
+        let mut touchpad = TestTouchpad::new();
+        touchpad.set_event_log(true);
+        touchpad
+            .touch_event(1u64, [0.0, 0.0], TouchPhase::Start)
+            .touch_event(2u64, [5.0, 5.0], TouchPhase::Start)
+            .touch_event(1u64, [1.0, 0.0], TouchPhase::End);
+        let events: Vec<_> = touchpad.events().map(|e| (e.id, e.phase)).collect();
+        assert_eq!(
+            events,
+            vec![
+                (1, TouchPhase::Start),
+                (2, TouchPhase::Start),
+                (1, TouchPhase::End),
+            ]
+        );
+
+        touchpad.clear_taps();
+        assert_eq!(touchpad.events().count(), 0);
+    }
+}
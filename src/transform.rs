@@ -0,0 +1,241 @@
+use crate::mouse::MouseQuery;
+use crate::touch::{Touch, TouchQuery};
+
+/// A conversion from window coordinates into some other coordinate space,
+/// such as a viewport, normalized device coordinates or a game world.
+///
+/// Any `Fn([C; 2]) -> [C; 2]` is a transform, so a camera's
+/// screen-to-world function can be used directly.
+pub trait Transform<C> {
+    /// Convert a position in window coordinates into this space.
+    fn apply(&self, position: [C; 2]) -> [C; 2];
+}
+
+impl<C, F> Transform<C> for F
+where
+    F: Fn([C; 2]) -> [C; 2],
+{
+    fn apply(&self, position: [C; 2]) -> [C; 2] {
+        self(position)
+    }
+}
+
+/// A rectangular area of the window, mapped onto an output range.
+///
+/// By default, positions are relative to the viewport's top-left corner and
+/// measured in the same units as the window.
+///
+/// # Examples
+///
+/// ```rust
+/// use buttons::{Transform, Viewport};
+///
+/// let viewport = Viewport::new([100.0, 0.0], [200.0, 100.0]).ndc();
+/// assert_eq!(viewport.apply([200.0, 50.0]), [0.0, 0.0]);
+/// assert_eq!(viewport.apply([100.0, 0.0]), [-1.0, 1.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    origin: [f64; 2],
+    size: [f64; 2],
+    output_origin: [f64; 2],
+    output_size: [f64; 2],
+    flip_y: bool,
+}
+
+impl Viewport {
+    /// Create a viewport at `origin` with the given `size`, in window
+    /// coordinates.
+    pub fn new(origin: [f64; 2], size: [f64; 2]) -> Self {
+        Viewport {
+            origin,
+            size,
+            output_origin: [0.0, 0.0],
+            output_size: size,
+            flip_y: false,
+        }
+    }
+
+    /// Create the largest viewport with the aspect ratio of `content_size`
+    /// that fits in the window, centered with bars on either side. Positions
+    /// are measured in content units.
+    pub fn letterboxed(window_size: [f64; 2], content_size: [f64; 2]) -> Self {
+        let scale = f64::min(
+            window_size[0] / content_size[0],
+            window_size[1] / content_size[1],
+        );
+        let size = [content_size[0] * scale, content_size[1] * scale];
+        let origin = [
+            (window_size[0] - size[0]) / 2.0,
+            (window_size[1] - size[1]) / 2.0,
+        ];
+        Viewport::new(origin, size).output([0.0, 0.0], content_size)
+    }
+
+    /// Map the viewport onto the rectangle at `origin` with the given `size`.
+    pub fn output(mut self, origin: [f64; 2], size: [f64; 2]) -> Self {
+        self.output_origin = origin;
+        self.output_size = size;
+        self
+    }
+
+    /// Map the viewport onto the range 0 to 1 on both axes.
+    pub fn normalized(self) -> Self {
+        self.output([0.0, 0.0], [1.0, 1.0])
+    }
+
+    /// Map the viewport onto normalized device coordinates: -1 to 1 on both
+    /// axes, with y pointing up.
+    pub fn ndc(self) -> Self {
+        self.output([-1.0, -1.0], [2.0, 2.0]).flip_y(true)
+    }
+
+    /// Make y increase upwards instead of downwards.
+    pub fn flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    /// Returns `true` if the window position is inside the viewport, rather
+    /// than in a letterbox bar or elsewhere in the window.
+    pub fn contains(&self, [x, y]: [f64; 2]) -> bool {
+        let [ox, oy] = self.origin;
+        let [w, h] = self.size;
+        x >= ox && x < ox + w && y >= oy && y < oy + h
+    }
+}
+
+impl Transform<f64> for Viewport {
+    fn apply(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        let u = (x - self.origin[0]) / self.size[0];
+        let v = (y - self.origin[1]) / self.size[1];
+        let v = if self.flip_y { 1.0 - v } else { v };
+        [
+            self.output_origin[0] + u * self.output_size[0],
+            self.output_origin[1] + v * self.output_size[1],
+        ]
+    }
+}
+
+/// A view of a mouse with positions converted by a `Transform`.
+#[derive(Debug)]
+pub struct TransformedMouse<'a, M, T> {
+    mouse: &'a M,
+    transform: T,
+}
+
+impl<'a, M, T> TransformedMouse<'a, M, T>
+where
+    M: MouseQuery,
+    T: Transform<M::Coord>,
+{
+    pub fn new(mouse: &'a M, transform: T) -> Self {
+        TransformedMouse { mouse, transform }
+    }
+
+    /// Returns the underlying mouse.
+    pub fn mouse(&self) -> &'a M {
+        self.mouse
+    }
+
+    /// Returns the position of the mouse pointer in the transformed space.
+    pub fn position(&self) -> [M::Coord; 2] {
+        self.transform.apply(self.mouse.position())
+    }
+
+    /// Returns the transformed position of the pointer, or `None` if it is
+    /// outside the window.
+    pub fn position_in_window(&self) -> Option<[M::Coord; 2]> {
+        self.mouse
+            .position_in_window()
+            .map(|p| self.transform.apply(p))
+    }
+
+    /// Returns `true` if the given button is currently held down.
+    pub fn down(&self, button: &M::Button) -> bool {
+        self.mouse.down(button)
+    }
+
+    /// Returns `true` if the given button was pressed this frame.
+    pub fn pressed(&self, button: &M::Button) -> bool {
+        self.mouse.pressed(button)
+    }
+
+    /// Returns `true` if the given button was released this frame.
+    pub fn released(&self, button: &M::Button) -> bool {
+        self.mouse.released(button)
+    }
+}
+
+/// A view of a touchpad with touch positions converted by a `Transform`.
+#[derive(Debug)]
+pub struct TransformedTouchpad<'a, P, T> {
+    touchpad: &'a P,
+    transform: T,
+}
+
+impl<'a, P, T> TransformedTouchpad<'a, P, T>
+where
+    P: TouchQuery,
+    P::TouchId: Clone,
+    T: Transform<P::Coord>,
+{
+    pub fn new(touchpad: &'a P, transform: T) -> Self {
+        TransformedTouchpad {
+            touchpad,
+            transform,
+        }
+    }
+
+    /// Returns the underlying touchpad.
+    pub fn touchpad(&self) -> &'a P {
+        self.touchpad
+    }
+
+    /// Returns the first registered touch, in the transformed space.
+    pub fn first_touch(&self) -> Option<Touch<P::TouchId, P::Coord>> {
+        self.touchpad.first_touch().map(|t| self.transformed(t))
+    }
+
+    /// Returns a touch by its `id`, in the transformed space.
+    pub fn touch<I: AsRef<P::TouchId>>(&self, id: I) -> Option<Touch<P::TouchId, P::Coord>> {
+        self.touchpad.touch(id).map(|t| self.transformed(t))
+    }
+
+    /// Returns an iterator over all active touches, in the transformed space.
+    pub fn touches(&self) -> impl Iterator<Item = Touch<P::TouchId, P::Coord>> + '_ {
+        self.touchpad.touches().map(|t| self.transformed(t))
+    }
+
+    fn transformed(&self, touch: &Touch<P::TouchId, P::Coord>) -> Touch<P::TouchId, P::Coord> {
+        Touch {
+            position: self.transform.apply(touch.position),
+            ..touch.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Mouse, MouseInterface};
+
+    #[test]
+    fn letterboxed_viewport_uses_content_units() {
+        let viewport = Viewport::letterboxed([400.0, 200.0], [100.0, 100.0]);
+        assert!(!viewport.contains([50.0, 100.0]));
+        assert_eq!(viewport.apply([100.0, 0.0]), [0.0, 0.0]);
+        assert_eq!(viewport.apply([200.0, 100.0]), [50.0, 50.0]);
+    }
+
+    #[test]
+    fn transformed_mouse_uses_closures() {
+        let mut mouse: Mouse<u8, f64> = Mouse::new();
+        mouse.move_to([10.0, 20.0]);
+        let world = |[x, y]: [f64; 2]| [x / 10.0 + 5.0, y / 10.0];
+        assert_eq!(mouse.transformed(world).position(), [6.0, 2.0]);
+
+        mouse.leave_window();
+        assert_eq!(mouse.transformed(world).position_in_window(), None);
+    }
+}